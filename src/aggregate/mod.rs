@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod test;
+
+use core::ops::{Deref,DerefMut};
+
+use CircularBuffer;
+
+///An associative binary operation with an identity element.
+///
+///`combine` must be associative, but does not need to be commutative.
+pub trait Monoid{
+	type Value;
+
+	///Returns the identity element (`combine(identity(),x) == combine(x,identity()) == x`).
+	fn identity(&self) -> Self::Value;
+
+	///Combines two values, where `a` is the more recently queued one.
+	fn combine(&self,a: &Self::Value,b: &Self::Value) -> Self::Value;
+}
+
+///Monoid constructed from an identity element and a closure.
+#[derive(Copy,Clone,Debug)]
+pub struct FnMonoid<V,F>{
+	pub identity: V,
+	pub combine: F,
+}
+
+impl<V,F> Monoid for FnMonoid<V,F> where
+	V: Clone,
+	F: Fn(&V,&V) -> V
+{
+	type Value = V;
+
+	#[inline]
+	fn identity(&self) -> V{self.identity.clone()}
+
+	#[inline]
+	fn combine(&self,a: &V,b: &V) -> V{(self.combine)(a,b)}
+}
+
+///Circular buffer keeping track of the fold of all its elements under a monoid.
+///
+///The fold is in the order from the most recently queued element to the oldest (the order of `CircularBuffer::iter`).
+///Uses the two-stack algorithm: the older part of the window is kept as a stack of partial folds,
+///and the newer part as a single fold, making `queue` amortized O(1) and `fold` O(1).
+pub struct WindowAggregator<M: Monoid,L = Box<[<M as Monoid>::Value]>>{
+	buffer: CircularBuffer<M::Value,L>,
+	monoid: M,
+	///Fold of the elements queued since the last rebuild.
+	newer: M::Value,
+	///Partial folds of the older elements. The last one is the fold of all of them.
+	older: Vec<M::Value>,
+}
+
+impl<M,L> WindowAggregator<M,L> where
+	M: Monoid,
+	L: Deref<Target=[M::Value]>
+{
+	///Constructs the structure from an already filled buffer.
+	pub fn new(buffer: CircularBuffer<M::Value,L>,monoid: M) -> Self{
+		let mut out = WindowAggregator{
+			newer: monoid.identity(),
+			older: Vec::with_capacity(buffer.len()),
+			buffer,
+			monoid,
+		};
+		out.rebuild();
+		out
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<M::Value,L>{&self.buffer}
+
+	///Returns the monoid.
+	#[inline(always)]
+	pub fn monoid(&self) -> &M{&self.monoid}
+
+	///Deconstructs the structure into the underlying buffer and the monoid.
+	#[inline(always)]
+	pub fn into_inner(self) -> (CircularBuffer<M::Value,L>,M){(self.buffer,self.monoid)}
+
+	///Returns the fold of all elements, from the most recently queued to the oldest.
+	pub fn fold(&self) -> M::Value{
+		match self.older.last(){
+			Some(older) => self.monoid.combine(&self.newer,older),
+			None => self.monoid.combine(&self.newer,&self.monoid.identity()),
+		}
+	}
+
+	///Moves all elements to the older part.
+	fn rebuild(&mut self){
+		self.older.clear();
+		let mut iter = self.buffer.iter();
+		if let Some(first) = iter.next(){
+			let mut fold = self.monoid.combine(first,&self.monoid.identity());
+			for elem in iter{
+				let next = self.monoid.combine(&fold,elem);
+				self.older.push(fold);
+				fold = next;
+			}
+			self.older.push(fold);
+		}
+		self.newer = self.monoid.identity();
+	}
+}
+
+impl<M,L> WindowAggregator<M,L> where
+	M: Monoid,
+	L: DerefMut<Target=[M::Value]>
+{
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,elem: M::Value) -> M::Value{
+		if self.older.is_empty(){
+			self.rebuild();
+		}
+		self.older.pop();
+		self.newer = self.monoid.combine(&elem,&self.newer);
+		self.buffer.queue(elem)
+	}
+}
+
+impl<M,L> Clone for WindowAggregator<M,L> where
+	M: Monoid + Clone,
+	M::Value: Clone,
+	L: Clone
+{
+	fn clone(&self) -> Self{
+		WindowAggregator{
+			buffer: self.buffer.clone(),
+			monoid: self.monoid.clone(),
+			newer: self.newer.clone(),
+			older: self.older.clone(),
+		}
+	}
+}
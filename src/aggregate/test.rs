@@ -0,0 +1,36 @@
+use super::*;
+use test_util::{Sum,concat};
+
+#[test]
+fn test_fold(){
+	let l = WindowAggregator::new(CircularBuffer::from(Box::new([1,2,3u32]) as Box<[u32]>),Sum);
+	assert_eq!(l.fold(),6);
+
+	let l = WindowAggregator::new(CircularBuffer::from(vec!["a".to_string(),"b".to_string(),"c".to_string()].into_boxed_slice()),concat());
+	assert_eq!(l.fold(),"abc");
+}
+
+#[test]
+fn test_queue(){
+	let mut l = WindowAggregator::new(CircularBuffer::from(Box::new([1,2,3u32]) as Box<[u32]>),Sum);
+	assert_eq!(l.queue(10),3);
+	assert_eq!(l.fold(),13);
+	assert_eq!(l.queue(20),2);
+	assert_eq!(l.fold(),31);
+	assert_eq!(l.queue(30),1);
+	assert_eq!(l.fold(),60);
+	assert_eq!(l.queue(0),10);
+	assert_eq!(l.fold(),50);
+}
+
+#[test]
+fn test_non_commutative(){
+	for len in 1..7{
+		let mut l = WindowAggregator::new(CircularBuffer::from(vec![String::new();len].into_boxed_slice()),concat());
+		for c in "abcdefghijklmnopqrstuvwxyz".chars(){
+			l.queue(c.to_string());
+			let expected: String = l.buffer().iter().map(|s| &**s).collect();
+			assert_eq!(l.fold(),expected);
+		}
+	}
+}
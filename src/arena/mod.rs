@@ -0,0 +1,275 @@
+//!Ring of variable-length byte records.
+
+#[cfg(test)]
+mod test;
+
+use core::mem;
+
+///Size of the length header in front of each record.
+const HEADER: usize = mem::size_of::<u32>();
+///Header value marking the unused end of the buffer before a wrap around.
+const PADDING: u32 = u32::MAX;
+
+///Ring storing length-headed records of varying size back to back in one byte buffer.
+///
+///Writing a record evicts as many of the oldest records as needed.
+///A record is never split across the end of the buffer: when it does not fit before the end, the rest is padded and the record is written at the start.
+///Every record starts at a multiple of the alignment, so its bytes can be cast to types of that alignment without copying.
+#[derive(Debug)]
+pub struct RecordRing{
+	storage: Box<[u8]>,
+	///Offset of the aligned start in `storage`.
+	base: usize,
+	capacity: usize,
+	///Size of the header slot, which records are also rounded up to a multiple of.
+	unit: usize,
+	///Offset of the oldest record.
+	head: usize,
+	///Offset where the next record is written.
+	tail: usize,
+	///Number of bytes in use, including headers and padding.
+	used: usize,
+	len: usize,
+	evicted: u64,
+}
+
+impl Clone for RecordRing{
+	///Clones the ring into new storage, aligning it anew as the alignment of the storage may differ.
+	fn clone(&self) -> Self{
+		let storage = vec![0u8;self.storage.len()].into_boxed_slice();
+		let mut out = RecordRing{
+			base: storage.as_ptr().align_offset(self.unit),
+			storage,
+			..*self
+		};
+		out.storage[out.base..out.base + self.capacity].copy_from_slice(&self.storage[self.base..self.base + self.capacity]);
+		out
+	}
+}
+
+impl RecordRing{
+	///Constructs an empty ring of `capacity` bytes, with records aligned to the header size.
+	///
+	///# Panics
+	///
+	///When `capacity` is too small to hold a record header and a byte of payload.
+	#[inline]
+	pub fn new(capacity: usize) -> Self{
+		RecordRing::with_alignment(capacity,1)
+	}
+
+	///Constructs an empty ring of `capacity` bytes (rounded down to a multiple of the record alignment), with the payload of each record aligned to `align` bytes.
+	///
+	///# Panics
+	///
+	///When `align` is not a power of two, or when `capacity` is too small to hold a record header and a byte of payload.
+	pub fn with_alignment(capacity: usize,align: usize) -> Self{
+		assert!(align.is_power_of_two(),"alignment must be a power of two");
+		let unit = align.max(HEADER);
+		let capacity = capacity / unit * unit;
+		assert!(capacity >= 2 * unit,"capacity too small for a record");
+
+		let storage = vec![0u8;capacity + unit - 1].into_boxed_slice();
+		let base = storage.as_ptr().align_offset(unit);
+		RecordRing{
+			storage,
+			base,
+			capacity,
+			unit,
+			head: 0,
+			tail: 0,
+			used: 0,
+			len: 0,
+			evicted: 0,
+		}
+	}
+
+	///Returns the size of the buffer in bytes.
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.capacity}
+
+	///Returns the alignment of the records.
+	#[inline(always)]
+	pub fn alignment(&self) -> usize{self.unit}
+
+	///Returns the number of bytes in use, including headers and padding.
+	#[inline(always)]
+	pub fn used(&self) -> usize{self.used}
+
+	///Returns the number of records.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.len}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.len == 0}
+
+	///Returns the number of records evicted so far.
+	#[inline(always)]
+	pub fn evicted(&self) -> u64{self.evicted}
+
+	///Returns the length of the largest record which fits.
+	#[inline]
+	pub fn max_record_len(&self) -> usize{
+		(self.capacity - self.unit).min(PADDING as usize - 1)
+	}
+
+	pub fn clear(&mut self){
+		self.head = 0;
+		self.tail = 0;
+		self.used = 0;
+		self.len = 0;
+	}
+
+	///Returns an iterator over the records from the oldest to the most recent.
+	#[inline]
+	pub fn iter(&self) -> Iter<'_>{
+		Iter{ring: self,offset: self.head,remaining: self.len}
+	}
+
+	///Returns the oldest record.
+	#[inline]
+	pub fn oldest(&self) -> Option<&[u8]>{
+		self.iter().next()
+	}
+
+	///Appends a copy of the record, evicting the oldest records as needed.
+	///
+	///# Panics
+	///
+	///When `record.len() > self.max_record_len()`.
+	#[inline]
+	pub fn push(&mut self,record: &[u8]) -> &mut [u8]{
+		let out = self.reserve(record.len());
+		out.copy_from_slice(record);
+		out
+	}
+
+	///Appends a record of `len` bytes to be written in place, evicting the oldest records as needed.
+	///The contents of the returned record are unspecified.
+	///
+	///# Panics
+	///
+	///When `len > self.max_record_len()`.
+	pub fn reserve(&mut self,len: usize) -> &mut [u8]{
+		assert!(len <= self.max_record_len(),"record larger than the capacity");
+		let size = self.unit + round_up(len,self.unit);
+
+		let offset = loop{
+			if self.used == 0{
+				self.head = 0;
+				self.tail = 0;
+			}
+			let (offset,needed) = if self.tail + size <= self.capacity{
+				(self.tail,size)
+			}else{
+				(0,self.capacity - self.tail + size)
+			};
+			if self.capacity - self.used >= needed{
+				break offset;
+			}
+			self.evict();
+			self.evicted += 1;
+		};
+
+		if offset != self.tail{
+			if self.tail < self.capacity{
+				self.write_header(self.tail,PADDING);
+			}
+			self.used += self.capacity - self.tail;
+		}
+		self.write_header(offset,len as u32);
+		self.tail = offset + size;
+		self.used += size;
+		self.len += 1;
+
+		let start = self.base + offset + self.unit;
+		&mut self.storage[start..start + len]
+	}
+
+	///Removes and returns a copy of the oldest record.
+	pub fn pop(&mut self) -> Option<Vec<u8>>{
+		let out = self.oldest()?.to_vec();
+		self.evict();
+		Some(out)
+	}
+
+	///Removes the oldest record, and the padding following it.
+	fn evict(&mut self){
+		let len = self.read_header(self.head) as usize;
+		let size = self.unit + round_up(len,self.unit);
+		self.advance(size);
+		self.len -= 1;
+		if self.used > 0 && (self.head == self.capacity || self.read_header(self.head) == PADDING){
+			self.advance(self.capacity - self.head);
+		}
+	}
+
+	#[inline]
+	fn advance(&mut self,size: usize){
+		self.used -= size;
+		self.head += size;
+		if self.head == self.capacity{
+			self.head = 0;
+		}
+	}
+
+	#[inline]
+	fn read_header(&self,offset: usize) -> u32{
+		let start = self.base + offset;
+		let mut bytes = [0;HEADER];
+		bytes.copy_from_slice(&self.storage[start..start + HEADER]);
+		u32::from_ne_bytes(bytes)
+	}
+
+	#[inline]
+	fn write_header(&mut self,offset: usize,value: u32){
+		let start = self.base + offset;
+		self.storage[start..start + HEADER].copy_from_slice(&value.to_ne_bytes());
+	}
+}
+
+impl<'r> IntoIterator for &'r RecordRing{
+	type Item = &'r [u8];
+	type IntoIter = Iter<'r>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter{self.iter()}
+}
+
+#[inline(always)]
+fn round_up(len: usize,unit: usize) -> usize{
+	(len + unit - 1) & !(unit - 1)
+}
+
+///Iterator over the records of a `RecordRing`, from the oldest to the most recent.
+#[derive(Clone,Debug)]
+pub struct Iter<'r>{
+	ring: &'r RecordRing,
+	offset: usize,
+	remaining: usize,
+}
+
+impl<'r> Iterator for Iter<'r>{
+	type Item = &'r [u8];
+
+	fn next(&mut self) -> Option<Self::Item>{
+		if self.remaining == 0{
+			return None;
+		}
+		if self.offset == self.ring.capacity || self.ring.read_header(self.offset) == PADDING{
+			self.offset = 0;
+		}
+		let len = self.ring.read_header(self.offset) as usize;
+		let start = self.ring.base + self.offset + self.ring.unit;
+		self.offset += self.ring.unit + round_up(len,self.ring.unit);
+		self.remaining -= 1;
+		Some(&self.ring.storage[start..start + len])
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize,Option<usize>){
+		(self.remaining,Some(self.remaining))
+	}
+}
+
+impl<'r> ExactSizeIterator for Iter<'r>{}
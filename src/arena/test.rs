@@ -0,0 +1,119 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+fn records(ring: &RecordRing) -> Vec<Vec<u8>>{
+	ring.iter().map(|record| record.to_vec()).collect()
+}
+
+#[test]
+fn test_push_evict(){
+	let mut ring = RecordRing::new(32);
+	ring.push(b"abc");
+	ring.push(b"defgh");
+	ring.push(b"");
+	assert_eq!(ring.len(),3);
+	assert_eq!(ring.used(),8 + 12 + 4);
+	assert_eq!(records(&ring),[&b"abc"[..],b"defgh",b""]);
+
+	//Does not fit before the end, so the end is padded and the records overlapping the start evicted
+	ring.push(b"ijklmn");
+	assert_eq!(records(&ring),[&b""[..],b"ijklmn"]);
+	assert_eq!(ring.evicted(),2);
+	assert_eq!(ring.used(),4 + 8 + 12);
+	assert_eq!(ring.oldest(),Some(&b""[..]));
+
+	ring.push(b"o");
+	assert_eq!(records(&ring),[&b""[..],b"ijklmn",b"o"]);
+	assert_eq!(ring.pop().unwrap(),b"");
+	assert_eq!(records(&ring),[&b"ijklmn"[..],b"o"]);
+	assert_eq!(ring.used(),12 + 8);
+	assert_eq!(ring.evicted(),2);
+}
+
+#[test]
+fn test_record_of_max_len(){
+	let mut ring = RecordRing::new(16);
+	assert_eq!(ring.max_record_len(),12);
+	ring.push(b"a");
+	ring.push(b"b");
+	ring.push(&[7;12]);
+	assert_eq!(records(&ring),[vec![7;12]]);
+	assert_eq!(ring.evicted(),2);
+	ring.push(b"c");
+	assert_eq!(records(&ring),[b"c"]);
+}
+
+#[test]
+#[should_panic]
+fn test_record_too_large(){
+	RecordRing::new(16).reserve(13);
+}
+
+#[test]
+fn test_reserve_aligned(){
+	let mut ring = RecordRing::with_alignment(100,16);
+	assert_eq!(ring.capacity(),96);
+	assert_eq!(ring.alignment(),16);
+	for i in 0..10u64{
+		let record = ring.reserve(8 + (i as usize % 3) * 8);
+		record[..8].copy_from_slice(&i.to_ne_bytes());
+	}
+	for record in &ring{
+		assert_eq!(record.as_ptr() as usize % 16,0);
+	}
+	let first: Vec<u64> = ring.iter().map(|record|{
+		let mut bytes = [0;8];
+		bytes.copy_from_slice(&record[..8]);
+		u64::from_ne_bytes(bytes)
+	}).collect();
+	assert_eq!(first,[8,9]);
+}
+
+#[test]
+fn test_clone_aligned(){
+	let mut ring = RecordRing::with_alignment(256,64);
+	for i in 0..5u8{
+		ring.push(&[i;10]);
+	}
+	//Keep the clones alive so that they are allocated at different addresses
+	let clones: Vec<RecordRing> = (0..20).map(|_| ring.clone()).collect();
+	for clone in &clones{
+		assert!(clone.iter().eq(ring.iter()));
+		for record in clone{
+			assert_eq!(record.as_ptr() as usize % 64,0);
+		}
+	}
+}
+
+#[test]
+fn test_against_model(){
+	let mut ring = RecordRing::with_alignment(200,8);
+	let mut model: VecDeque<Vec<u8>> = VecDeque::new();
+	let mut pushed = 0u64;
+	let mut state = 0x9e37_79b9u32;
+	for _ in 0..5000{
+		state ^= state << 13;
+		state ^= state >> 17;
+		state ^= state << 5;
+		if state.is_multiple_of(7){
+			assert_eq!(ring.pop(),model.pop_front());
+			continue;
+		}
+		let len = (state >> 8) as usize % (ring.max_record_len() + 1);
+		let record: Vec<u8> = (0..len).map(|i| (pushed as usize + i) as u8).collect();
+		ring.push(&record);
+		model.push_back(record);
+		pushed += 1;
+
+		assert!(ring.used() <= ring.capacity());
+		while model.len() > ring.len(){
+			model.pop_front();
+		}
+		assert_eq!(records(&ring),Vec::from(model.clone()));
+	}
+	assert!(ring.evicted() > 0);
+	ring.clear();
+	assert!(ring.is_empty());
+	assert_eq!(ring.iter().next(),None);
+}
@@ -0,0 +1,226 @@
+//!Asynchronous variant of `channel`, independent of any executor.
+//!
+//!The receiver is woken through `core::task::Waker`. With the `futures` feature,
+//!`Receiver` implements `Stream`, and `windows` turns any stream into a stream of window snapshots.
+
+#[cfg(test)]
+mod test;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context,Poll,Waker};
+use std::sync::{Arc,Mutex,MutexGuard};
+use std::sync::mpsc::{SendError,TryRecvError};
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+
+#[cfg(feature = "futures")]
+use CircularBuffer;
+use channel::State;
+
+struct Shared<T>{
+	state: Mutex<(State<T>,Option<Waker>)>,
+}
+
+impl<T> Shared<T>{
+	#[inline]
+	fn lock(&self) -> MutexGuard<'_,(State<T>,Option<Waker>)>{
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+///Creates a channel buffering at most `capacity` unread messages.
+///
+///# Panics
+///
+///When `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>,Receiver<T>){
+	let shared = Arc::new(Shared{
+		state: Mutex::new((State::new(capacity),None)),
+	});
+	(Sender{shared: shared.clone()},Receiver{shared})
+}
+
+///The sending half of a channel. Cloning it creates another sender of the same channel.
+pub struct Sender<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T>{
+	///Sends a message without blocking, waking the receiver.
+	///When the channel is full, the oldest unread message is dropped from the channel and returned.
+	///Fails when the receiver has been dropped.
+	pub fn send(&self,msg: T) -> Result<Option<T>,SendError<T>>{
+		let (out,waker) = {
+			let mut state = self.shared.lock();
+			if !state.0.receiver{
+				return Err(SendError(msg));
+			}
+			(state.0.push(msg),state.1.take())
+		};
+		if let Some(waker) = waker{
+			waker.wake();
+		}
+		Ok(out)
+	}
+}
+
+impl<T> Clone for Sender<T>{
+	fn clone(&self) -> Self{
+		self.shared.lock().0.senders += 1;
+		Sender{shared: self.shared.clone()}
+	}
+}
+
+impl<T> Drop for Sender<T>{
+	fn drop(&mut self){
+		let waker = {
+			let mut state = self.shared.lock();
+			state.0.senders -= 1;
+			if state.0.senders == 0{state.1.take()}else{None}
+		};
+		if let Some(waker) = waker{
+			waker.wake();
+		}
+	}
+}
+
+///The receiving half of a channel.
+pub struct Receiver<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T>{
+	///Receives the oldest unread message without waiting.
+	pub fn try_recv(&self) -> Result<T,TryRecvError>{
+		let mut state = self.shared.lock();
+		match state.0.take(){
+			Some(msg) => Ok(msg),
+			None if state.0.senders == 0 => Err(TryRecvError::Disconnected),
+			None => Err(TryRecvError::Empty),
+		}
+	}
+
+	///Polls for the oldest unread message.
+	///Returns `Ready(None)` when the channel is empty and all senders have been dropped.
+	///Otherwise when empty, the task of the context is woken when a message arrives.
+	pub fn poll_recv(&mut self,cx: &mut Context<'_>) -> Poll<Option<T>>{
+		let mut state = self.shared.lock();
+		if let Some(msg) = state.0.take(){
+			return Poll::Ready(Some(msg));
+		}
+		if state.0.senders == 0{
+			return Poll::Ready(None);
+		}
+		match state.1{
+			Some(ref waker) if waker.will_wake(cx.waker()) => {},
+			_ => state.1 = Some(cx.waker().clone()),
+		}
+		Poll::Pending
+	}
+
+	///Returns a future receiving the oldest unread message (See `poll_recv`).
+	#[inline]
+	pub fn recv(&mut self) -> Recv<'_,T>{
+		Recv{receiver: self}
+	}
+
+	///Returns the number of messages which were overwritten before being received.
+	pub fn dropped(&self) -> u64{
+		self.shared.lock().0.dropped
+	}
+}
+
+impl<T> Drop for Receiver<T>{
+	fn drop(&mut self){
+		let mut state = self.shared.lock();
+		state.0.receiver = false;
+		state.1 = None;
+	}
+}
+
+#[cfg(feature = "futures")]
+impl<T> Stream for Receiver<T>{
+	type Item = T;
+
+	#[inline]
+	fn poll_next(self: Pin<&mut Self>,cx: &mut Context<'_>) -> Poll<Option<T>>{
+		self.get_mut().poll_recv(cx)
+	}
+}
+
+///Future returned by `Receiver::recv`.
+#[must_use = "futures do nothing unless polled"]
+pub struct Recv<'r,T: 'r>{
+	receiver: &'r mut Receiver<T>,
+}
+
+impl<'r,T> Future for Recv<'r,T>{
+	type Output = Option<T>;
+
+	#[inline]
+	fn poll(self: Pin<&mut Self>,cx: &mut Context<'_>) -> Poll<Option<T>>{
+		self.get_mut().receiver.poll_recv(cx)
+	}
+}
+
+///Stream of snapshots of the `size` most recent items of another stream (See `windows`).
+#[cfg(feature = "futures")]
+pub struct Windows<S: Stream>{
+	stream: S,
+	size: usize,
+	///Items collected before the first window is filled, from the oldest.
+	initial: Vec<S::Item>,
+	window: Option<CircularBuffer<S::Item>>,
+}
+
+///Turns a stream into a stream of snapshots of its `size` most recent items.
+///The first snapshot is yielded when `size` items have arrived, and then one for every item.
+///
+///# Panics
+///
+///When `size` is 0.
+#[cfg(feature = "futures")]
+pub fn windows<S: Stream>(stream: S,size: usize) -> Windows<S>{
+	assert!(size > 0);
+	Windows{
+		stream,
+		size,
+		initial: Vec::with_capacity(size),
+		window: None,
+	}
+}
+
+#[cfg(feature = "futures")]
+impl<S> Stream for Windows<S> where
+	S: Stream + Unpin,
+	S::Item: Clone + Unpin
+{
+	type Item = CircularBuffer<S::Item>;
+
+	fn poll_next(self: Pin<&mut Self>,cx: &mut Context<'_>) -> Poll<Option<Self::Item>>{
+		let this = self.get_mut();
+		loop{
+			let item = match Pin::new(&mut this.stream).poll_next(cx){
+				Poll::Ready(Some(item)) => item,
+				Poll::Ready(None) => return Poll::Ready(None),
+				Poll::Pending => return Poll::Pending,
+			};
+
+			if let Some(ref mut window) = this.window{
+				window.queue(item);
+				return Poll::Ready(Some(window.clone()));
+			}
+
+			this.initial.push(item);
+			if this.initial.len() == this.size{
+				let mut initial = ::core::mem::take(&mut this.initial);
+				initial.reverse();
+				let window = CircularBuffer::from(initial);
+				this.window = Some(window.clone());
+				return Poll::Ready(Some(window));
+			}
+		}
+	}
+}
@@ -0,0 +1,165 @@
+use super::*;
+use std::task::Wake;
+use std::thread;
+use std::time::Duration;
+
+///Waker unparking the thread which is blocking on a future.
+struct Unparker(thread::Thread);
+
+impl Wake for Unparker{
+	fn wake(self: Arc<Self>){
+		self.0.unpark();
+	}
+}
+
+///Minimal executor running a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output{
+	let mut future = Box::pin(future);
+	let waker = Waker::from(Arc::new(Unparker(thread::current())));
+	let mut cx = Context::from_waker(&waker);
+	loop{
+		if let Poll::Ready(out) = future.as_mut().poll(&mut cx){
+			return out;
+		}
+		thread::park();
+	}
+}
+
+///Waker counting the number of times it was woken.
+struct Counter(Mutex<usize>);
+
+impl Wake for Counter{
+	fn wake(self: Arc<Self>){
+		*self.0.lock().unwrap() += 1;
+	}
+}
+
+#[test]
+fn test_poll_recv(){
+	let counter = Arc::new(Counter(Mutex::new(0)));
+	let waker = Waker::from(counter.clone());
+	let mut cx = Context::from_waker(&waker);
+
+	let (s,mut r) = channel(2);
+	assert_eq!(r.poll_recv(&mut cx),Poll::Pending);
+	assert_eq!(*counter.0.lock().unwrap(),0);
+
+	assert_eq!(s.send('a'),Ok(None));
+	assert_eq!(*counter.0.lock().unwrap(),1);
+	assert_eq!(s.send('b'),Ok(None));
+	assert_eq!(s.send('c'),Ok(Some('a')));
+	assert_eq!(*counter.0.lock().unwrap(),1);
+	assert_eq!(r.dropped(),1);
+
+	assert_eq!(r.poll_recv(&mut cx),Poll::Ready(Some('b')));
+	assert_eq!(r.try_recv(),Ok('c'));
+	assert_eq!(r.poll_recv(&mut cx),Poll::Pending);
+
+	drop(s);
+	assert_eq!(*counter.0.lock().unwrap(),2);
+	assert_eq!(r.poll_recv(&mut cx),Poll::Ready(None));
+	assert_eq!(r.try_recv(),Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn test_send_after_receiver_dropped(){
+	let (s,r) = channel(2);
+	drop(r);
+	assert_eq!(s.send(1),Err(SendError(1)));
+}
+
+#[test]
+fn test_block_on_recv(){
+	let (s,mut r) = channel(8);
+	let sender = thread::spawn(move ||{
+		for i in 0..100{
+			s.send(i).unwrap();
+			if i % 10 == 0{
+				thread::sleep(Duration::from_millis(1));
+			}
+		}
+	});
+
+	let mut received = Vec::new();
+	while let Some(x) = block_on(r.recv()){
+		received.push(x);
+	}
+	sender.join().unwrap();
+
+	for w in received.windows(2){
+		assert!(w[0] < w[1]);
+	}
+	assert_eq!(received.len() as u64 + r.dropped(),100);
+	assert_eq!(received.last(),Some(&99));
+}
+
+#[cfg(feature = "futures")]
+mod stream{
+	use super::*;
+
+	///Stream yielding the items of an iterator, being pending before each one.
+	struct Slow<I>{
+		iter: I,
+		ready: bool,
+	}
+
+	impl<I: Iterator + Unpin> Stream for Slow<I>{
+		type Item = I::Item;
+
+		fn poll_next(self: Pin<&mut Self>,cx: &mut Context<'_>) -> Poll<Option<I::Item>>{
+			let this = self.get_mut();
+			this.ready = !this.ready;
+			if this.ready{
+				Poll::Ready(this.iter.next())
+			}else{
+				cx.waker().wake_by_ref();
+				Poll::Pending
+			}
+		}
+	}
+
+	struct Next<'s,S: 's>(&'s mut S);
+
+	impl<'s,S: Stream + Unpin> Future for Next<'s,S>{
+		type Output = Option<S::Item>;
+
+		fn poll(self: Pin<&mut Self>,cx: &mut Context<'_>) -> Poll<Option<S::Item>>{
+			Pin::new(&mut *self.get_mut().0).poll_next(cx)
+		}
+	}
+
+	fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item>{
+		let mut out = Vec::new();
+		while let Some(x) = block_on(Next(&mut stream)){
+			out.push(x);
+		}
+		out
+	}
+
+	#[test]
+	fn test_receiver_stream(){
+		let (s,r) = channel(4);
+		s.send(1).unwrap();
+		s.send(2).unwrap();
+		drop(s);
+		assert_eq!(collect(r),vec![1,2]);
+	}
+
+	#[test]
+	fn test_windows(){
+		let windows: Vec<Vec<char>> = collect(windows(Slow{iter: "abcde".chars(),ready: false},3))
+			.into_iter()
+			.map(|w| w.iter().cloned().collect())
+			.collect();
+		assert_eq!(windows,vec![
+			vec!['c','b','a'],
+			vec!['d','c','b'],
+			vec!['e','d','c'],
+		]);
+	}
+
+	#[test]
+	fn test_windows_short(){
+		assert_eq!(collect(windows(Slow{iter: "ab".chars(),ready: false},3)).len(),0);
+	}
+}
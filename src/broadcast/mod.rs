@@ -0,0 +1,171 @@
+//!Broadcast ring with a single `Writer` and any number of `Reader`s, each reading at its own pace.
+//!
+//!All readers share the same fixed storage. A reader which falls behind by more than the capacity
+//!is told how many elements it missed through `ReadError::Lagged`.
+
+#[cfg(test)]
+mod test;
+
+use std::sync::{Arc,RwLock};
+use std::sync::atomic::{AtomicU64,Ordering};
+
+struct Slot<T>{
+	///Sequence number of the element, or `u64::MAX` when never written.
+	seq: u64,
+	elem: Option<T>,
+}
+
+struct Shared<T>{
+	slots: Box<[RwLock<Slot<T>>]>,
+	///Sequence number of the next element to be written.
+	next: AtomicU64,
+}
+
+impl<T> Shared<T>{
+	#[inline(always)]
+	fn capacity(&self) -> u64{self.slots.len() as u64}
+
+	#[inline(always)]
+	fn slot(&self,seq: u64) -> &RwLock<Slot<T>>{
+		&self.slots[(seq % self.capacity()) as usize]
+	}
+
+	///Returns the sequence number of the oldest available element.
+	#[inline]
+	fn oldest(&self,next: u64) -> u64{
+		next.saturating_sub(self.capacity())
+	}
+}
+
+///Creates a broadcast ring keeping the `capacity` most recently written elements.
+///
+///# Panics
+///
+///When `capacity` is 0.
+pub fn broadcast<T>(capacity: usize) -> Writer<T>{
+	assert!(capacity > 0);
+	Writer{
+		shared: Arc::new(Shared{
+			slots: (0..capacity).map(|_| RwLock::new(Slot{seq: u64::MAX,elem: None})).collect::<Vec<_>>().into_boxed_slice(),
+			next: AtomicU64::new(0),
+		}),
+	}
+}
+
+///The writing half of a broadcast ring.
+pub struct Writer<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Writer<T>{
+	///Returns the maximum number of available elements.
+	#[inline]
+	pub fn capacity(&self) -> usize{self.shared.slots.len()}
+
+	///Returns the sequence number which the next written element will get.
+	#[inline]
+	pub fn next_seq(&self) -> u64{self.shared.next.load(Ordering::Acquire)}
+
+	///Enqueues the given element, returning the element which was overwritten when the ring is full.
+	///
+	///This only waits for readers which are currently cloning the overwritten element.
+	pub fn queue(&mut self,elem: T) -> Option<T>{
+		let seq = self.shared.next.load(Ordering::Relaxed);
+		let out = {
+			let mut slot = self.shared.slot(seq).write().unwrap_or_else(|e| e.into_inner());
+			slot.seq = seq;
+			slot.elem.replace(elem)
+		};
+		self.shared.next.store(seq + 1,Ordering::Release);
+		out
+	}
+
+	///Creates a reader starting at the oldest available element.
+	pub fn reader(&self) -> Reader<T>{
+		Reader{
+			cursor: self.shared.oldest(self.next_seq()),
+			shared: self.shared.clone(),
+		}
+	}
+}
+
+///Why a read did not return an element.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum ReadError{
+	///The reader has read all written elements.
+	Empty,
+	///The writer has overwritten the given number of elements that the reader has not read yet.
+	///The reader has been moved to the oldest available element.
+	Lagged(u64),
+}
+
+///A reading handle of a broadcast ring with its own cursor.
+///
+///Cloning a reader creates a new one at the same position.
+pub struct Reader<T>{
+	shared: Arc<Shared<T>>,
+	///Sequence number of the next element to read.
+	cursor: u64,
+}
+
+impl<T> Reader<T>{
+	///Returns the sequence number of the next element to read.
+	#[inline(always)]
+	pub fn cursor(&self) -> u64{self.cursor}
+
+	///Returns the number of elements which are available to read, including the overwritten ones.
+	#[inline]
+	pub fn pending(&self) -> u64{
+		self.shared.next.load(Ordering::Acquire).saturating_sub(self.cursor)
+	}
+
+	///Moves the cursor to the oldest available element.
+	pub fn resync(&mut self){
+		self.cursor = self.shared.oldest(self.shared.next.load(Ordering::Acquire));
+	}
+
+	///Moves the cursor past the newest element, so that only elements written after this call are read.
+	pub fn skip_all(&mut self){
+		self.cursor = self.shared.next.load(Ordering::Acquire);
+	}
+
+	///Reads the next element by cloning it.
+	pub fn read(&mut self) -> Result<T,ReadError> where
+		T: Clone
+	{
+		let next = self.shared.next.load(Ordering::Acquire);
+		if self.cursor >= next{
+			return Err(ReadError::Empty);
+		}
+
+		let oldest = self.shared.oldest(next);
+		if self.cursor < oldest{
+			let lagged = oldest - self.cursor;
+			self.cursor = oldest;
+			return Err(ReadError::Lagged(lagged));
+		}
+
+		let slot = self.shared.slot(self.cursor).read().unwrap_or_else(|e| e.into_inner());
+		if slot.seq == self.cursor{
+			self.cursor += 1;
+			Ok(slot.elem.clone().expect("written slot"))
+		}else{
+			//Overwritten after loading `next`. The writer may not have advanced `next` yet, but the slot already holds a newer element
+			let written = slot.seq;
+			drop(slot);
+			let oldest = self.shared.oldest(self.shared.next.load(Ordering::Acquire).max(written + 1));
+			let lagged = oldest - self.cursor;
+			self.cursor = oldest;
+			Err(ReadError::Lagged(lagged))
+		}
+	}
+}
+
+impl<T> Clone for Reader<T>{
+	fn clone(&self) -> Self{
+		Reader{
+			shared: self.shared.clone(),
+			cursor: self.cursor,
+		}
+	}
+}
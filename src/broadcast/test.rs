@@ -0,0 +1,113 @@
+use super::*;
+use std::thread;
+
+#[test]
+fn test_read(){
+	let mut w = broadcast(3);
+	let mut r = w.reader();
+	assert_eq!(r.read(),Err(ReadError::Empty));
+
+	assert_eq!(w.queue('a'),None);
+	assert_eq!(w.queue('b'),None);
+	assert_eq!(r.pending(),2);
+	assert_eq!(r.read(),Ok('a'));
+	assert_eq!(r.read(),Ok('b'));
+	assert_eq!(r.read(),Err(ReadError::Empty));
+	assert_eq!(r.cursor(),2);
+}
+
+#[test]
+fn test_reader_history(){
+	let mut w = broadcast(3);
+	for c in "abcde".chars(){
+		w.queue(c);
+	}
+
+	let mut r = w.reader();
+	assert_eq!(r.cursor(),2);
+	assert_eq!(r.read(),Ok('c'));
+
+	let mut r2 = r.clone();
+	assert_eq!(r.read(),Ok('d'));
+	assert_eq!(r2.read(),Ok('d'));
+
+	r2.skip_all();
+	assert_eq!(r2.read(),Err(ReadError::Empty));
+	w.queue('f');
+	assert_eq!(r2.read(),Ok('f'));
+}
+
+#[test]
+fn test_lagged(){
+	let mut w = broadcast(2);
+	let mut r = w.reader();
+	assert_eq!(w.queue('a'),None);
+	assert_eq!(w.queue('b'),None);
+	assert_eq!(w.queue('c'),Some('a'));
+	assert_eq!(w.queue('d'),Some('b'));
+	assert_eq!(w.queue('e'),Some('c'));
+
+	assert_eq!(r.pending(),5);
+	assert_eq!(r.read(),Err(ReadError::Lagged(3)));
+	assert_eq!(r.read(),Ok('d'));
+
+	w.queue('f');
+	w.queue('g');
+	w.queue('h');
+	r.resync();
+	assert_eq!(r.read(),Ok('g'));
+	assert_eq!(r.read(),Ok('h'));
+}
+
+#[test]
+fn test_overwritten_before_next(){
+	let mut w = broadcast(2);
+	let mut r = w.reader();
+	w.queue('a');
+	w.queue('b');
+
+	//The writer has replaced the slot of 'a', but has not advanced `next` yet
+	{
+		let mut slot = w.shared.slot(2).write().unwrap();
+		slot.seq = 2;
+		slot.elem = Some('c');
+	}
+	assert_eq!(r.read(),Err(ReadError::Lagged(1)));
+	assert_eq!(r.cursor(),1);
+	assert_eq!(r.read(),Ok('b'));
+}
+
+#[test]
+fn test_concurrent_readers(){
+	const COUNT: u64 = 50_000;
+	let mut w = broadcast(16);
+	let readers: Vec<_> = (0..3).map(|_|{
+		let mut r = w.reader();
+		thread::spawn(move ||{
+			let mut expected = 0;
+			let mut read = 0;
+			while expected < COUNT{
+				match r.read(){
+					Ok(x) => {
+						assert_eq!(x,expected);
+						expected += 1;
+						read += 1;
+					},
+					Err(ReadError::Lagged(n)) => expected += n,
+					Err(ReadError::Empty) => thread::yield_now(),
+				}
+			}
+			read
+		})
+	}).collect();
+
+	for i in 0..COUNT{
+		w.queue(i);
+		if i % 64 == 0{
+			thread::yield_now();
+		}
+	}
+	for r in readers{
+		assert!(r.join().unwrap() > 0);
+	}
+}
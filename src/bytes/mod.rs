@@ -0,0 +1,255 @@
+//!Byte oriented rings which copy in bulk instead of queueing one byte at a time.
+
+#[cfg(test)]
+mod test;
+
+use core::{fmt,str};
+use std::io;
+
+///Fixed size ring of bytes which may be partially filled.
+///
+///Unlike `CircularBuffer`, the bytes are stored from the oldest to the most recently written,
+///so that the contents form at most two contiguous slices.
+///Writing through `io::Write` keeps the most recent bytes, overwriting the oldest ones.
+#[derive(Clone,Debug,Eq,PartialEq,Hash)]
+pub struct ByteRing{
+	list: Box<[u8]>,
+	///Internal index of the oldest byte.
+	start: usize,
+	len: usize,
+}
+
+impl ByteRing{
+	///Constructs an empty ring able to hold `capacity` bytes.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	pub fn new(capacity: usize) -> Self{
+		assert!(capacity > 0);
+		ByteRing{
+			list: vec![0;capacity].into_boxed_slice(),
+			start: 0,
+			len: 0,
+		}
+	}
+
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.list.len()}
+
+	///Returns the number of bytes in the ring.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.len}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.len == 0}
+
+	#[inline(always)]
+	pub fn is_full(&self) -> bool{self.len == self.list.len()}
+
+	///Returns the number of bytes which can be written without overwriting anything.
+	#[inline(always)]
+	pub fn free(&self) -> usize{self.list.len() - self.len}
+
+	///Returns the byte at the given index, counting from the oldest byte.
+	///
+	///# Panics
+	///
+	///When `index >= self.len()`.
+	#[inline]
+	pub fn get(&self,index: usize) -> u8{
+		assert!(index < self.len);
+		self.list[(self.start + index) % self.list.len()]
+	}
+
+	///Returns all bytes from the oldest to the most recently written as two slices.
+	///The second slice is empty unless the contents wrap around the end of the internal storage.
+	#[inline]
+	pub fn contents(&self) -> (&[u8],&[u8]){
+		self.range(0,self.len)
+	}
+
+	///Returns `len` bytes starting at `offset` (counting from the oldest byte) as two slices.
+	///
+	///# Panics
+	///
+	///When `offset + len > self.len()`.
+	pub fn range(&self,offset: usize,len: usize) -> (&[u8],&[u8]){
+		assert!(offset + len <= self.len);
+		let capacity = self.list.len();
+		let start = (self.start + offset) % capacity;
+		if start + len <= capacity{
+			(&self.list[start..start + len],&[])
+		}else{
+			(&self.list[start..],&self.list[..start + len - capacity])
+		}
+	}
+
+	///Copies all bytes from the oldest to the most recently written into a vec.
+	pub fn to_vec(&self) -> Vec<u8>{
+		let (a,b) = self.contents();
+		let mut out = Vec::with_capacity(a.len() + b.len());
+		out.extend_from_slice(a);
+		out.extend_from_slice(b);
+		out
+	}
+
+	///Returns the free part of the ring, after the most recently written byte, as two mutable slices.
+	///Use `commit` to make bytes written there part of the contents.
+	pub fn free_mut(&mut self) -> (&mut [u8],&mut [u8]){
+		let capacity = self.list.len();
+		let free = capacity - self.len;
+		let end = (self.start + self.len) % capacity;
+		if end + free <= capacity{
+			(&mut self.list[end..end + free],&mut [])
+		}else{
+			let (second,first) = self.list.split_at_mut(end);
+			(first,&mut second[..end + free - capacity])
+		}
+	}
+
+	///Appends `count` bytes from the start of the free part (See `free_mut`) to the contents.
+	///
+	///# Panics
+	///
+	///When `count > self.free()`.
+	#[inline]
+	pub fn commit(&mut self,count: usize){
+		assert!(count <= self.free());
+		self.len += count;
+	}
+
+	///Removes the `count` oldest bytes.
+	///
+	///# Panics
+	///
+	///When `count > self.len()`.
+	pub fn consume(&mut self,count: usize){
+		assert!(count <= self.len);
+		self.len -= count;
+		//An empty ring starts over at the beginning to keep the free part contiguous
+		self.start = if self.len == 0{0}else{(self.start + count) % self.list.len()};
+	}
+
+	///Removes all bytes.
+	#[inline]
+	pub fn clear(&mut self){
+		self.start = 0;
+		self.len = 0;
+	}
+
+	///Appends as many bytes as there is free space for, returning the number of appended bytes.
+	pub fn push(&mut self,bytes: &[u8]) -> usize{
+		let count = bytes.len().min(self.free());
+		self.copy_to_free(&bytes[..count]);
+		self.len += count;
+		count
+	}
+
+	///Appends all bytes, overwriting the oldest bytes when the ring is full.
+	///Returns the number of overwritten bytes.
+	pub fn push_overwrite(&mut self,bytes: &[u8]) -> usize{
+		let capacity = self.list.len();
+		let overwritten = (self.len + bytes.len()).saturating_sub(capacity);
+		if bytes.len() >= capacity{
+			self.list.copy_from_slice(&bytes[bytes.len() - capacity..]);
+			self.start = 0;
+			self.len = capacity;
+		}else{
+			self.consume(overwritten);
+			self.copy_to_free(bytes);
+			self.len += bytes.len();
+		}
+		overwritten
+	}
+
+	///Copies the bytes to the start of the free part, which must be large enough.
+	fn copy_to_free(&mut self,bytes: &[u8]){
+		let (a,b) = self.free_mut();
+		if bytes.len() <= a.len(){
+			a[..bytes.len()].copy_from_slice(bytes);
+		}else{
+			let (x,y) = bytes.split_at(a.len());
+			a.copy_from_slice(x);
+			b[..y.len()].copy_from_slice(y);
+		}
+	}
+}
+
+impl io::Write for ByteRing{
+	///Appends all bytes, overwriting the oldest bytes when the ring is full.
+	#[inline]
+	fn write(&mut self,bytes: &[u8]) -> io::Result<usize>{
+		self.push_overwrite(bytes);
+		Ok(bytes.len())
+	}
+
+	#[inline]
+	fn flush(&mut self) -> io::Result<()>{Ok(())}
+}
+
+///Ring of UTF-8 text keeping the most recently written bytes.
+///
+///When the oldest bytes are overwritten, any partially overwritten character is removed as well,
+///so that the contents always are valid UTF-8.
+#[derive(Clone,Debug,Eq,PartialEq,Hash)]
+pub struct TailString{
+	ring: ByteRing,
+}
+
+impl TailString{
+	///Constructs an empty ring able to hold `capacity` bytes.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	#[inline]
+	pub fn new(capacity: usize) -> Self{
+		TailString{ring: ByteRing::new(capacity)}
+	}
+
+	///Returns the underlying bytes.
+	#[inline(always)]
+	pub fn as_ring(&self) -> &ByteRing{&self.ring}
+
+	///Returns the text from the oldest to the most recently written as two byte slices.
+	///The slices are only valid UTF-8 when concatenated, because the wrap around may split a character.
+	#[inline]
+	pub fn contents(&self) -> (&[u8],&[u8]){
+		self.ring.contents()
+	}
+
+	#[inline]
+	pub fn clear(&mut self){
+		self.ring.clear();
+	}
+
+	///Appends the text, removing the oldest characters when the ring is full.
+	pub fn push_str(&mut self,s: &str){
+		if self.ring.push_overwrite(s.as_bytes()) > 0{
+			//Continuation bytes have the form 0b10xxxxxx
+			let mut partial = 0;
+			while partial < self.ring.len() && self.ring.get(partial) & 0b1100_0000 == 0b1000_0000{
+				partial += 1;
+			}
+			self.ring.consume(partial);
+		}
+	}
+}
+
+impl fmt::Write for TailString{
+	#[inline]
+	fn write_str(&mut self,s: &str) -> fmt::Result{
+		self.push_str(s);
+		Ok(())
+	}
+}
+
+impl fmt::Display for TailString{
+	fn fmt(&self,f: &mut fmt::Formatter) -> fmt::Result{
+		match self.contents(){
+			(a,[]) => f.write_str(str::from_utf8(a).map_err(|_| fmt::Error)?),
+			_ => f.write_str(str::from_utf8(&self.ring.to_vec()).map_err(|_| fmt::Error)?),
+		}
+	}
+}
@@ -0,0 +1,92 @@
+use super::*;
+use std::io::Write;
+use core::fmt::Write as FmtWrite;
+
+#[test]
+fn test_push(){
+	let mut l = ByteRing::new(4);
+	assert!(l.is_empty());
+	assert_eq!(l.push(b"abc"),3);
+	assert_eq!(l.contents(),(&b"abc"[..],&b""[..]));
+	assert_eq!(l.push(b"de"),1);
+	assert!(l.is_full());
+
+	l.consume(2);
+	assert_eq!(l.push(b"fgh"),2);
+	assert_eq!(l.contents(),(&b"cd"[..],&b"fg"[..]));
+	assert_eq!(l.get(2),b'f');
+	assert_eq!(l.range(1,2),(&b"d"[..],&b"f"[..]));
+
+	l.consume(4);
+	assert_eq!(l.free_mut().0.len(),4);
+}
+
+#[test]
+fn test_push_overwrite(){
+	let mut l = ByteRing::new(4);
+	assert_eq!(l.push_overwrite(b"ab"),0);
+	assert_eq!(l.push_overwrite(b"cde"),1);
+	assert_eq!(l.contents(),(&b"bcd"[..],&b"e"[..]));
+	assert_eq!(l.push_overwrite(b"f"),1);
+	assert_eq!(l.to_vec(),b"cdef");
+	assert_eq!(l.push_overwrite(b"0123456789"),10);
+	assert_eq!(l.contents(),(&b"6789"[..],&b""[..]));
+}
+
+#[test]
+fn test_free_commit(){
+	let mut l = ByteRing::new(5);
+	l.push(b"abcd");
+	l.consume(3);
+	{
+		let (a,b) = l.free_mut();
+		assert_eq!((a.len(),b.len()),(1,3));
+		a[0] = b'e';
+		b[0] = b'f';
+	}
+	l.commit(2);
+	assert_eq!(l.to_vec(),b"def");
+}
+
+#[test]
+fn test_io_write(){
+	let mut l = ByteRing::new(8);
+	for i in 0..5{
+		writeln!(l,"line {}",i).unwrap();
+	}
+	assert_eq!(l.to_vec(),b"\nline 4\n");
+}
+
+#[test]
+fn test_against_naive(){
+	let mut l = ByteRing::new(7);
+	let mut naive: Vec<u8> = Vec::new();
+	for i in 0..100u8{
+		let bytes: Vec<u8> = (0..i % 11).map(|j| i.wrapping_mul(31).wrapping_add(j)).collect();
+		l.write_all(&bytes).unwrap();
+		naive.extend_from_slice(&bytes);
+		let start = naive.len().saturating_sub(7);
+		assert_eq!(l.to_vec(),&naive[start..]);
+	}
+}
+
+#[test]
+fn test_tail_string(){
+	let mut l = TailString::new(6);
+	write!(l,"abc").unwrap();
+	assert_eq!(l.to_string(),"abc");
+
+	//"é" is two bytes, "€" is three bytes
+	write!(l,"é€").unwrap();
+	assert_eq!(l.to_string(),"cé€");
+	write!(l,"x").unwrap();
+	assert_eq!(l.to_string(),"é€x");
+	write!(l,"y").unwrap();
+	assert_eq!(l.to_string(),"€xy");
+	assert_eq!(l.as_ring().len(),5);
+
+	write!(l,"€€").unwrap();
+	assert_eq!(l.to_string(),"€€");
+	write!(l,"z").unwrap();
+	assert_eq!(l.to_string(),"€z");
+}
@@ -0,0 +1,251 @@
+//!Bounded channel which overwrites the oldest unread message instead of blocking the sender.
+//!
+//!The interface mirrors `std::sync::mpsc`, and uses its error types.
+
+#[cfg(test)]
+mod test;
+
+use std::sync::{Arc,Condvar,Mutex,MutexGuard};
+use std::sync::mpsc::{RecvError,RecvTimeoutError,SendError,TryRecvError};
+use std::time::{Duration,Instant};
+
+use CircularBuffer;
+
+pub(crate) struct State<T>{
+	///The unread messages are the `unread` most recently queued ones.
+	buffer: CircularBuffer<Option<T>>,
+	unread: usize,
+	///Number of messages overwritten before being received.
+	pub(crate) dropped: u64,
+	pub(crate) senders: usize,
+	pub(crate) receiver: bool,
+}
+
+impl<T> State<T>{
+	pub(crate) fn new(capacity: usize) -> Self{
+		assert!(capacity > 0);
+		State{
+			buffer: (0..capacity).map(|_| None).collect(),
+			unread: 0,
+			dropped: 0,
+			senders: 1,
+			receiver: true,
+		}
+	}
+
+	///Queues a message, returning the oldest unread message if it was overwritten.
+	pub(crate) fn push(&mut self,msg: T) -> Option<T>{
+		let out = self.buffer.queue(Some(msg));
+		if out.is_some(){
+			self.dropped += 1;
+		}else{
+			self.unread += 1;
+		}
+		out
+	}
+
+	///Takes the oldest unread message.
+	pub(crate) fn take(&mut self) -> Option<T>{
+		if self.unread == 0{
+			return None;
+		}
+		self.unread -= 1;
+		self.buffer.get_mut(self.unread).take()
+	}
+}
+
+struct Shared<T>{
+	state: Mutex<State<T>>,
+	available: Condvar,
+}
+
+impl<T> Shared<T>{
+	#[inline]
+	fn lock(&self) -> MutexGuard<'_,State<T>>{
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+///Creates a channel buffering at most `capacity` unread messages.
+///
+///# Panics
+///
+///When `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>,Receiver<T>){
+	let shared = Arc::new(Shared{
+		state: Mutex::new(State::new(capacity)),
+		available: Condvar::new(),
+	});
+	(Sender{shared: shared.clone()},Receiver{shared})
+}
+
+///The sending half of a channel. Cloning it creates another sender of the same channel.
+pub struct Sender<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T>{
+	///Sends a message without blocking.
+	///When the channel is full, the oldest unread message is dropped from the channel and returned.
+	///Fails when the receiver has been dropped.
+	pub fn send(&self,msg: T) -> Result<Option<T>,SendError<T>>{
+		let out = {
+			let mut state = self.shared.lock();
+			if !state.receiver{
+				return Err(SendError(msg));
+			}
+			state.push(msg)
+		};
+		self.shared.available.notify_one();
+		Ok(out)
+	}
+}
+
+impl<T> Clone for Sender<T>{
+	fn clone(&self) -> Self{
+		self.shared.lock().senders += 1;
+		Sender{shared: self.shared.clone()}
+	}
+}
+
+impl<T> Drop for Sender<T>{
+	fn drop(&mut self){
+		let last = {
+			let mut state = self.shared.lock();
+			state.senders -= 1;
+			state.senders == 0
+		};
+		if last{
+			self.shared.available.notify_all();
+		}
+	}
+}
+
+///The receiving half of a channel.
+pub struct Receiver<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T>{
+	///Receives the oldest unread message without blocking.
+	pub fn try_recv(&self) -> Result<T,TryRecvError>{
+		let mut state = self.shared.lock();
+		match state.take(){
+			Some(msg) => Ok(msg),
+			None if state.senders == 0 => Err(TryRecvError::Disconnected),
+			None => Err(TryRecvError::Empty),
+		}
+	}
+
+	///Receives the oldest unread message, blocking until one arrives.
+	///Fails when the channel is empty and all senders have been dropped.
+	pub fn recv(&self) -> Result<T,RecvError>{
+		let mut state = self.shared.lock();
+		loop{
+			if let Some(msg) = state.take(){
+				return Ok(msg);
+			}
+			if state.senders == 0{
+				return Err(RecvError);
+			}
+			state = self.shared.available.wait(state).unwrap_or_else(|e| e.into_inner());
+		}
+	}
+
+	///Receives the oldest unread message, blocking until one arrives or until `timeout` has passed.
+	pub fn recv_timeout(&self,timeout: Duration) -> Result<T,RecvTimeoutError>{
+		let deadline = Instant::now() + timeout;
+		let mut state = self.shared.lock();
+		loop{
+			if let Some(msg) = state.take(){
+				return Ok(msg);
+			}
+			if state.senders == 0{
+				return Err(RecvTimeoutError::Disconnected);
+			}
+			let now = Instant::now();
+			if now >= deadline{
+				return Err(RecvTimeoutError::Timeout);
+			}
+			state = self.shared.available.wait_timeout(state,deadline - now).unwrap_or_else(|e| e.into_inner()).0;
+		}
+	}
+
+	///Returns the number of messages which were overwritten before being received.
+	pub fn dropped(&self) -> u64{
+		self.shared.lock().dropped
+	}
+
+	///Returns an iterator blocking for messages until all senders have been dropped.
+	#[inline]
+	pub fn iter(&self) -> Iter<'_,T>{
+		Iter{receiver: self}
+	}
+
+	///Returns an iterator over the currently unread messages, without blocking.
+	#[inline]
+	pub fn try_iter(&self) -> TryIter<'_,T>{
+		TryIter{receiver: self}
+	}
+}
+
+impl<T> Drop for Receiver<T>{
+	fn drop(&mut self){
+		self.shared.lock().receiver = false;
+	}
+}
+
+pub struct Iter<'r,T: 'r>{
+	receiver: &'r Receiver<T>,
+}
+
+impl<'r,T> Iterator for Iter<'r,T>{
+	type Item = T;
+
+	#[inline]
+	fn next(&mut self) -> Option<T>{
+		self.receiver.recv().ok()
+	}
+}
+
+pub struct TryIter<'r,T: 'r>{
+	receiver: &'r Receiver<T>,
+}
+
+impl<'r,T> Iterator for TryIter<'r,T>{
+	type Item = T;
+
+	#[inline]
+	fn next(&mut self) -> Option<T>{
+		self.receiver.try_recv().ok()
+	}
+}
+
+pub struct IntoIter<T>{
+	receiver: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T>{
+	type Item = T;
+
+	#[inline]
+	fn next(&mut self) -> Option<T>{
+		self.receiver.recv().ok()
+	}
+}
+
+impl<'r,T> IntoIterator for &'r Receiver<T>{
+	type Item = T;
+	type IntoIter = Iter<'r,T>;
+
+	#[inline]
+	fn into_iter(self) -> Iter<'r,T>{self.iter()}
+}
+
+impl<T> IntoIterator for Receiver<T>{
+	type Item = T;
+	type IntoIter = IntoIter<T>;
+
+	#[inline]
+	fn into_iter(self) -> IntoIter<T>{IntoIter{receiver: self}}
+}
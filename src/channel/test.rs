@@ -0,0 +1,93 @@
+use super::*;
+use std::thread;
+
+#[test]
+fn test_send_recv(){
+	let (s,r) = channel(3);
+	assert_eq!(r.try_recv(),Err(TryRecvError::Empty));
+
+	assert_eq!(s.send('a'),Ok(None));
+	assert_eq!(s.send('b'),Ok(None));
+	assert_eq!(r.recv(),Ok('a'));
+	assert_eq!(s.send('c'),Ok(None));
+	assert_eq!(s.send('d'),Ok(None));
+	assert_eq!(s.send('e'),Ok(Some('b')));
+	assert_eq!(r.dropped(),1);
+
+	assert_eq!(r.try_iter().collect::<String>(),"cde");
+	assert_eq!(r.try_recv(),Err(TryRecvError::Empty));
+}
+
+#[test]
+fn test_overwrite_wraps(){
+	let (s,r) = channel(2);
+	let mut expected = ::std::collections::VecDeque::new();
+	for i in 0..20{
+		expected.push_back(i);
+		let dropped = if expected.len() > 2{expected.pop_front()}else{None};
+		assert_eq!(s.send(i),Ok(dropped));
+		if i % 3 == 0{
+			assert_eq!(r.try_recv().ok(),expected.pop_front());
+		}
+	}
+	assert_eq!(r.try_iter().collect::<Vec<_>>(),vec![18,19]);
+}
+
+#[test]
+fn test_disconnect(){
+	let (s,r) = channel(2);
+	let s2 = s.clone();
+	s.send(1).unwrap();
+	drop(s);
+	s2.send(2).unwrap();
+	drop(s2);
+
+	assert_eq!(r.try_recv(),Ok(1));
+	assert_eq!(r.recv(),Ok(2));
+	assert_eq!(r.try_recv(),Err(TryRecvError::Disconnected));
+	assert_eq!(r.recv(),Err(RecvError));
+	assert_eq!(r.recv_timeout(Duration::from_millis(1)),Err(RecvTimeoutError::Disconnected));
+
+	let (s,r) = channel(2);
+	drop(r);
+	assert_eq!(s.send(1),Err(SendError(1)));
+}
+
+#[test]
+fn test_recv_timeout(){
+	let (s,r) = channel::<u32>(2);
+	assert_eq!(r.recv_timeout(Duration::from_millis(10)),Err(RecvTimeoutError::Timeout));
+
+	let sender = thread::spawn(move ||{
+		thread::sleep(Duration::from_millis(10));
+		s.send(7).unwrap();
+	});
+	assert_eq!(r.recv_timeout(Duration::from_secs(10)),Ok(7));
+	sender.join().unwrap();
+}
+
+#[test]
+fn test_iter_threads(){
+	let (s,r) = channel(4);
+	let senders: Vec<_> = (0..3).map(|t|{
+		let s = s.clone();
+		thread::spawn(move ||{
+			for i in 0..1000{
+				s.send((t,i)).unwrap();
+			}
+		})
+	}).collect();
+	drop(s);
+
+	let mut last = [None;3];
+	let mut count = 0;
+	for (t,i) in r.iter(){
+		assert!(last[t].is_none_or(|l| l < i));
+		last[t] = Some(i);
+		count += 1;
+	}
+	for sender in senders{
+		sender.join().unwrap();
+	}
+	assert_eq!(count as u64 + r.dropped(),3000);
+}
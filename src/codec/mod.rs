@@ -0,0 +1,289 @@
+//!Reassembly of frames from a byte stream buffered in a `ByteRing`.
+//!
+//!A `Decoder` locates the next frame in the buffered bytes. `Codec` returns frames which lie within
+//!one contiguous part of the ring without copying, and copies the ones straddling the wrap around into a scratch buffer.
+
+#[cfg(test)]
+mod test;
+
+use std::io::{self,Read};
+
+use bytes::ByteRing;
+use io_util::invalid_data;
+
+///Where a decoded frame is.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Decoded{
+	///The frame is the `len` bytes at `offset` of the data, and the first `consumed` bytes of the data are used up.
+	Range{offset: usize,len: usize,consumed: usize},
+	///The frame was written to the scratch buffer, and the first `consumed` bytes of the data are used up.
+	Scratch{consumed: usize},
+	///The first `consumed` bytes of the data are not a valid frame, and are skipped after reporting `error`.
+	Invalid{consumed: usize,error: &'static str},
+}
+
+///Locates frames in buffered bytes.
+pub trait Decoder{
+	///Decodes the next frame from the start of `data`, given as two slices which are contiguous when concatenated.
+	///Returns `None` when `data` does not contain a whole frame yet.
+	///`scratch` is empty when called, and may be used for frames which need to be transformed.
+	///
+	///Invalid frames should be reported as `Decoded::Invalid` so that decoding can continue after them.
+	///An error is passed on by `Codec::next_frame` without consuming any bytes.
+	fn decode(&mut self,data: (&[u8],&[u8]),scratch: &mut Vec<u8>) -> io::Result<Option<Decoded>>;
+}
+
+///Byte ring paired with a decoder.
+pub struct Codec<D>{
+	ring: ByteRing,
+	decoder: D,
+	scratch: Vec<u8>,
+	///Number of bytes used up by the previously returned frame, which still may be borrowed.
+	pending: usize,
+}
+
+impl<D: Decoder> Codec<D>{
+	///Constructs a codec able to buffer `capacity` bytes, which limits the size of a frame.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	pub fn new(capacity: usize,decoder: D) -> Self{
+		Codec{
+			ring: ByteRing::new(capacity),
+			decoder,
+			scratch: Vec::new(),
+			pending: 0,
+		}
+	}
+
+	#[inline(always)]
+	pub fn decoder(&self) -> &D{&self.decoder}
+
+	#[inline(always)]
+	pub fn decoder_mut(&mut self) -> &mut D{&mut self.decoder}
+
+	///Returns the buffered bytes which are not part of a returned frame.
+	#[inline]
+	pub fn buffer(&self) -> (&[u8],&[u8]){
+		self.ring.range(self.pending,self.ring.len() - self.pending)
+	}
+
+	///Buffers as many bytes as there is free space for, returning the number of buffered bytes.
+	pub fn feed(&mut self,bytes: &[u8]) -> usize{
+		self.release();
+		self.ring.push(bytes)
+	}
+
+	///Returns whether the ring is full after consuming the previously returned frame, so that nothing can be fed before `next_frame` returns more frames.
+	#[inline]
+	pub fn is_full(&self) -> bool{
+		self.ring.len() - self.pending == self.ring.capacity()
+	}
+
+	///Reads once from the reader into the free part of the ring, returning the number of read bytes, where 0 means the end of the reader.
+	///
+	///Fails with `WriteZero` when the ring is full (See `is_full`), so that it is not mistaken for the end of the reader.
+	pub fn read_from<R: Read>(&mut self,reader: &mut R) -> io::Result<usize>{
+		self.release();
+		if self.ring.is_full(){
+			return Err(io::Error::new(io::ErrorKind::WriteZero,"codec buffer is full"));
+		}
+		let count = reader.read(self.ring.free_mut().0)?;
+		self.ring.commit(count);
+		Ok(count)
+	}
+
+	///Returns the next frame, or `None` when more bytes are needed.
+	///
+	///Fails with `InvalidData` when the decoder finds an invalid frame, which is skipped by the next call,
+	///or when the ring is full without containing a whole frame, in which case the buffered bytes are discarded.
+	pub fn next_frame(&mut self) -> io::Result<Option<&[u8]>>{
+		self.release();
+		self.scratch.clear();
+		match self.decoder.decode(self.ring.contents(),&mut self.scratch)?{
+			None if self.ring.is_full() => {
+				self.pending = self.ring.len();
+				Err(invalid_data("frame larger than the capacity"))
+			},
+			None => Ok(None),
+			Some(Decoded::Range{offset,len,consumed}) => {
+				self.pending = consumed;
+				match self.ring.range(offset,len){
+					(a,[]) => Ok(Some(a)),
+					(a,b) => {
+						self.scratch.extend_from_slice(a);
+						self.scratch.extend_from_slice(b);
+						Ok(Some(&self.scratch))
+					},
+				}
+			},
+			Some(Decoded::Scratch{consumed}) => {
+				self.pending = consumed;
+				Ok(Some(&self.scratch))
+			},
+			Some(Decoded::Invalid{consumed,error}) => {
+				self.pending = consumed;
+				Err(invalid_data(error))
+			},
+		}
+	}
+
+	///Consumes the bytes of the previously returned frame.
+	#[inline]
+	fn release(&mut self){
+		self.ring.consume(self.pending);
+		self.pending = 0;
+	}
+}
+
+#[inline]
+fn data_len(data: (&[u8],&[u8])) -> usize{
+	data.0.len() + data.1.len()
+}
+
+#[inline]
+fn data_get(data: (&[u8],&[u8]),index: usize) -> u8{
+	if index < data.0.len(){data.0[index]}else{data.1[index - data.0.len()]}
+}
+
+///Finds the first `byte` at or after `from`.
+fn data_find(data: (&[u8],&[u8]),from: usize,byte: u8) -> Option<usize>{
+	let first = data.0.len();
+	if from < first{
+		if let Some(i) = data.0[from..].iter().position(|&b| b == byte){
+			return Some(from + i);
+		}
+	}
+	let from = from.saturating_sub(first);
+	data.1.get(from..).and_then(|rest| rest.iter().position(|&b| b == byte)).map(|i| first + from + i)
+}
+
+///Frames preceded by their length as an unsigned integer.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum LengthPrefixed{
+	U16BigEndian,
+	U16LittleEndian,
+	U32BigEndian,
+	U32LittleEndian,
+}
+
+impl Decoder for LengthPrefixed{
+	fn decode(&mut self,data: (&[u8],&[u8]),_: &mut Vec<u8>) -> io::Result<Option<Decoded>>{
+		let (size,big) = match *self{
+			LengthPrefixed::U16BigEndian    => (2,true),
+			LengthPrefixed::U16LittleEndian => (2,false),
+			LengthPrefixed::U32BigEndian    => (4,true),
+			LengthPrefixed::U32LittleEndian => (4,false),
+		};
+		if data_len(data) < size{
+			return Ok(None);
+		}
+		let len = (0..size).fold(0usize,|len,i|{
+			let byte = data_get(data,if big{i}else{size - 1 - i}) as usize;
+			(len << 8) | byte
+		});
+		match size.checked_add(len){
+			Some(consumed) if data_len(data) >= consumed => Ok(Some(Decoded::Range{offset: size,len,consumed})),
+			_ => Ok(None),
+		}
+	}
+}
+
+///Frames terminated by a line ending, which is not part of the frame.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Lines{
+	///Terminated by `\n`.
+	Lf,
+	///Terminated by `\r\n`. A lone `\n` is part of the line.
+	CrLf,
+}
+
+impl Decoder for Lines{
+	fn decode(&mut self,data: (&[u8],&[u8]),_: &mut Vec<u8>) -> io::Result<Option<Decoded>>{
+		let mut from = 0;
+		while let Some(end) = data_find(data,from,b'\n'){
+			match *self{
+				Lines::Lf => return Ok(Some(Decoded::Range{offset: 0,len: end,consumed: end + 1})),
+				Lines::CrLf if end > 0 && data_get(data,end - 1) == b'\r' => return Ok(Some(Decoded::Range{offset: 0,len: end - 1,consumed: end + 1})),
+				Lines::CrLf => from = end + 1,
+			}
+		}
+		Ok(None)
+	}
+}
+
+///Frames encoded with Consistent Overhead Byte Stuffing, each terminated by a zero byte.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct Cobs;
+
+impl Decoder for Cobs{
+	fn decode(&mut self,data: (&[u8],&[u8]),scratch: &mut Vec<u8>) -> io::Result<Option<Decoded>>{
+		let end = match data_find(data,0,0){
+			Some(end) => end,
+			None => return Ok(None),
+		};
+
+		let mut i = 0;
+		while i < end{
+			let code = data_get(data,i) as usize;
+			if i + code > end{
+				return Ok(Some(Decoded::Invalid{consumed: end + 1,error: "truncated COBS block"}));
+			}
+			scratch.extend((i + 1..i + code).map(|j| data_get(data,j)));
+			i += code;
+			if code != 0xFF && i < end{
+				scratch.push(0);
+			}
+		}
+		Ok(Some(Decoded::Scratch{consumed: end + 1}))
+	}
+}
+
+///Frames encoded with the Serial Line Internet Protocol, each terminated by `END` (0xC0).
+///Empty frames are skipped.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct Slip;
+
+impl Slip{
+	pub const END: u8 = 0xC0;
+	pub const ESC: u8 = 0xDB;
+	pub const ESC_END: u8 = 0xDC;
+	pub const ESC_ESC: u8 = 0xDD;
+}
+
+impl Decoder for Slip{
+	fn decode(&mut self,data: (&[u8],&[u8]),scratch: &mut Vec<u8>) -> io::Result<Option<Decoded>>{
+		let mut start = 0;
+		let end = loop{
+			match data_find(data,start,Slip::END){
+				Some(end) if end == start => start += 1,
+				Some(end) => break end,
+				None => return Ok(None),
+			}
+		};
+
+		//Frames without escapes are returned as they are
+		if data_find(data,start,Slip::ESC).is_none_or(|esc| esc > end){
+			return Ok(Some(Decoded::Range{offset: start,len: end - start,consumed: end + 1}));
+		}
+
+		let mut i = start;
+		while i < end{
+			match data_get(data,i){
+				Slip::ESC if i + 1 < end => {
+					i += 1;
+					match data_get(data,i){
+						Slip::ESC_END => scratch.push(Slip::END),
+						Slip::ESC_ESC => scratch.push(Slip::ESC),
+						_ => return Ok(Some(Decoded::Invalid{consumed: end + 1,error: "invalid SLIP escape"})),
+					}
+				},
+				Slip::ESC => return Ok(Some(Decoded::Invalid{consumed: end + 1,error: "invalid SLIP escape"})),
+				byte => scratch.push(byte),
+			}
+			i += 1;
+		}
+		Ok(Some(Decoded::Scratch{consumed: end + 1}))
+	}
+}
@@ -0,0 +1,138 @@
+use super::*;
+
+///Returns the next frame and whether it was copied into the scratch buffer.
+fn next<D: Decoder>(codec: &mut Codec<D>) -> Option<(Vec<u8>,bool)>{
+	let frame = codec.next_frame().unwrap()?;
+	let (frame,ptr) = (frame.to_vec(),frame.as_ptr());
+	Some((frame,!codec.scratch.is_empty() && ptr == codec.scratch.as_ptr()))
+}
+
+#[test]
+fn test_length_prefixed_seam(){
+	let mut c = Codec::new(8,LengthPrefixed::U16BigEndian);
+	assert_eq!(c.feed(&[0,3,b'a',b'b']),4);
+	assert_eq!(next(&mut c),None);
+	assert_eq!(c.feed(&[b'c',0,2]),3);
+	assert_eq!(next(&mut c),Some((b"abc".to_vec(),false)));
+
+	//The next frame straddles the end of the list
+	assert_eq!(c.feed(&[b'd',b'e',0,1]),4);
+	assert_eq!(next(&mut c),Some((b"de".to_vec(),true)));
+	assert_eq!(c.buffer(),(&[0,1][..],&[][..]));
+	assert_eq!(next(&mut c),None);
+	c.feed(b"f");
+	assert_eq!(next(&mut c),Some((b"f".to_vec(),false)));
+	assert_eq!(next(&mut c),None);
+}
+
+#[test]
+fn test_length_prefixed_orders(){
+	let mut c = Codec::new(16,LengthPrefixed::U32LittleEndian);
+	c.feed(&[2,0,0,0,7,8]);
+	assert_eq!(next(&mut c).unwrap().0,[7,8]);
+
+	let mut c = Codec::new(16,LengthPrefixed::U32BigEndian);
+	c.feed(&[0,0,0,1,9]);
+	assert_eq!(next(&mut c).unwrap().0,[9]);
+
+	let mut c = Codec::new(16,LengthPrefixed::U16LittleEndian);
+	c.feed(&[0,0,1,0,5]);
+	assert_eq!(next(&mut c).unwrap().0,[]);
+	assert_eq!(next(&mut c).unwrap().0,[5]);
+}
+
+#[test]
+fn test_frame_too_large(){
+	let mut c = Codec::new(4,LengthPrefixed::U16BigEndian);
+	c.feed(&[0,5,1,2]);
+	assert_eq!(c.next_frame().unwrap_err().kind(),io::ErrorKind::InvalidData);
+
+	//The buffered bytes are discarded
+	assert_eq!(next(&mut c),None);
+	c.feed(&[0,1,3]);
+	assert_eq!(next(&mut c).unwrap().0,[3]);
+}
+
+#[test]
+fn test_lines(){
+	let mut c = Codec::new(8,Lines::Lf);
+	c.feed(b"ab\r\ncd");
+	assert_eq!(next(&mut c),Some((b"ab\r".to_vec(),false)));
+	c.feed(b"efg\nh");
+	assert_eq!(next(&mut c),Some((b"cdefg".to_vec(),true)));
+	assert_eq!(next(&mut c),None);
+
+	let mut c = Codec::new(16,Lines::CrLf);
+	c.feed(b"a\nb\r\n\r\nc\r");
+	assert_eq!(next(&mut c).unwrap().0,b"a\nb");
+	assert_eq!(next(&mut c).unwrap().0,b"");
+	assert_eq!(next(&mut c),None);
+	c.feed(b"\n");
+	assert_eq!(next(&mut c).unwrap().0,b"c");
+}
+
+#[test]
+fn test_cobs(){
+	let mut c = Codec::new(16,Cobs);
+	c.feed(&[0x03,0x11,0x22,0x02,0x33,0x00,0x01,0x01,0x00]);
+	assert_eq!(next(&mut c),Some((vec![0x11,0x22,0x00,0x33],true)));
+	assert_eq!(next(&mut c).unwrap().0,[0x00]);
+	assert_eq!(next(&mut c),None);
+
+	c.feed(&[0x05,0x01,0x00,0x02,0x44,0x00]);
+	assert_eq!(c.next_frame().unwrap_err().to_string(),"truncated COBS block");
+	assert_eq!(next(&mut c).unwrap().0,[0x44]);
+}
+
+#[test]
+fn test_cobs_long_block(){
+	let data: Vec<u8> = (1..=254).collect();
+	let mut encoded = vec![0xFF];
+	encoded.extend_from_slice(&data);
+	encoded.extend_from_slice(&[0x02,0x07,0x00]);
+
+	let mut c = Codec::new(300,Cobs);
+	c.feed(&encoded);
+	let mut expected = data.clone();
+	expected.push(0x07);
+	assert_eq!(next(&mut c).unwrap().0,expected);
+}
+
+#[test]
+fn test_slip(){
+	let mut c = Codec::new(8,Slip);
+	c.feed(&[Slip::END,1,2,Slip::END,Slip::END]);
+	assert_eq!(next(&mut c),Some((vec![1,2],false)));
+	assert_eq!(next(&mut c),None);
+
+	c.feed(&[3,Slip::ESC,Slip::ESC_END,Slip::ESC,Slip::ESC_ESC,Slip::END]);
+	assert_eq!(next(&mut c),Some((vec![3,Slip::END,Slip::ESC],true)));
+
+	c.feed(&[Slip::ESC,4,Slip::END,5,Slip::END]);
+	assert_eq!(c.next_frame().unwrap_err().kind(),io::ErrorKind::InvalidData);
+	assert_eq!(next(&mut c).unwrap().0,[5]);
+}
+
+#[test]
+fn test_read_from(){
+	let mut c = Codec::new(8,Lines::Lf);
+	let mut input = &b"one\ntwo\nthree\n"[..];
+	let mut lines = Vec::new();
+	while c.read_from(&mut input).unwrap() > 0{
+		while let Some(line) = c.next_frame().unwrap(){
+			lines.push(String::from_utf8(line.to_vec()).unwrap());
+		}
+	}
+	assert_eq!(lines,["one","two","three"]);
+
+	//A full ring is not the end of the reader
+	let mut c = Codec::new(4,Lines::Lf);
+	let mut input = &b"ab\ncd\n"[..];
+	assert_eq!(c.read_from(&mut input).unwrap(),4);
+	assert!(c.is_full());
+	assert_eq!(c.read_from(&mut input).unwrap_err().kind(),io::ErrorKind::WriteZero);
+	assert_eq!(next(&mut c).unwrap().0,b"ab");
+	assert!(!c.is_full());
+	assert_eq!(c.read_from(&mut input).unwrap(),2);
+	assert_eq!(next(&mut c).unwrap().0,b"cd");
+}
@@ -0,0 +1,36 @@
+//!Helpers for reading binary headers and reporting malformed input.
+
+#![allow(dead_code)] //Not every helper is used with every combination of features
+
+use std::io;
+
+#[inline]
+fn array<const N: usize>(bytes: &[u8]) -> [u8;N]{
+	let mut buf = [0;N];
+	buf.copy_from_slice(bytes);
+	buf
+}
+
+///Reads a little-endian `u32` from exactly 4 bytes.
+#[inline]
+pub(crate) fn read_u32_le(bytes: &[u8]) -> u32{u32::from_le_bytes(array(bytes))}
+
+///Reads a little-endian `u64` from exactly 8 bytes.
+#[inline]
+pub(crate) fn read_u64_le(bytes: &[u8]) -> u64{u64::from_le_bytes(array(bytes))}
+
+///Reads a native-endian `u32` from exactly 4 bytes.
+#[inline]
+pub(crate) fn read_u32_ne(bytes: &[u8]) -> u32{u32::from_ne_bytes(array(bytes))}
+
+///Reads a native-endian `u64` from exactly 8 bytes.
+#[inline]
+pub(crate) fn read_u64_ne(bytes: &[u8]) -> u64{u64::from_ne_bytes(array(bytes))}
+
+pub(crate) fn invalid_data(msg: &'static str) -> io::Error{
+	io::Error::new(io::ErrorKind::InvalidData,msg)
+}
+
+pub(crate) fn invalid_input(msg: &'static str) -> io::Error{
+	io::Error::new(io::ErrorKind::InvalidInput,msg)
+}
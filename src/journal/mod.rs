@@ -0,0 +1,343 @@
+//!Crash consistent ring of variable-length records stored in a file.
+//!
+//!The file starts with a header of `HEADER_LEN` bytes (`MAGIC`, `VERSION` and the capacity of the data region), followed by the data region.
+//!Records are written back to back in the data region, starting at 8 byte aligned offsets, and wrap around to offset 0 when the next record does not fit before the end.
+//!Each record has a little-endian header of a sequence number (8 bytes), the payload length (4 bytes) and a CRC32 of the sequence number, length and payload (4 bytes).
+//!
+//!No positions are stored. Instead, `Journal::open` recovers them by scanning for the longest run of valid records with consecutive sequence numbers.
+//!Torn or corrupted records end the run, so the records after them are discarded.
+//!Everything outside of the recovered records is then overwritten with zeros, so that discarded records cannot line up with records appended later.
+
+#[cfg(test)]
+mod test;
+
+use core::convert::TryFrom;
+use std::collections::VecDeque;
+use std::fs::{File,OpenOptions};
+use std::io::{self,Read,Seek,SeekFrom,Write};
+use std::path::Path;
+
+use crc32fast::Hasher;
+
+use io_util::{invalid_data,invalid_input,read_u32_le,read_u64_le};
+
+pub const MAGIC: [u8;8] = *b"FCBUFJNL";
+pub const VERSION: u32 = 1;
+pub const HEADER_LEN: u64 = 32;
+///Size of the header of each record.
+pub const RECORD_HEADER_LEN: usize = 16;
+///Alignment of the records in the data region.
+const ALIGN: usize = 8;
+///Number of bytes by which `Journal::open` extends a truncated data region at most.
+pub const MAX_TRUNCATION: u64 = 64 * 1024;
+
+///When to flush written records to the storage device.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum FsyncPolicy{
+	///After every appended record.
+	Always,
+	///After every `n` appended records.
+	Every(u32),
+	///Only when `Journal::sync` is called, leaving it to the operating system otherwise.
+	Never,
+}
+
+///Position of a record in the data region.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+struct Entry{
+	offset: usize,
+	seq: u64,
+	len: usize,
+}
+
+impl Entry{
+	#[inline]
+	fn end(&self) -> usize{
+		self.offset + record_size(self.len)
+	}
+}
+
+///Durable ring of variable-length records, evicting the oldest records when the data region is full.
+#[derive(Debug)]
+pub struct Journal{
+	file: File,
+	capacity: usize,
+	policy: FsyncPolicy,
+	///Records from the oldest to the most recent.
+	index: VecDeque<Entry>,
+	///Offset where the next record is written.
+	tail: usize,
+	next_seq: u64,
+	///Number of records appended since the last sync.
+	unsynced: u32,
+}
+
+impl Journal{
+	///Creates (or truncates) the file at `path` with a data region of `capacity` bytes, rounded down to the record alignment.
+	///
+	///Fails with `InvalidInput` when `capacity` cannot hold a record.
+	pub fn create<P: AsRef<Path>>(path: P,capacity: usize,policy: FsyncPolicy) -> io::Result<Self>{
+		let capacity = capacity / ALIGN * ALIGN;
+		if capacity <= RECORD_HEADER_LEN{
+			return Err(invalid_input("capacity too small for a record"));
+		}
+
+		let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+		let mut header = [0;HEADER_LEN as usize];
+		header[0..8].copy_from_slice(&MAGIC);
+		header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+		header[16..24].copy_from_slice(&(capacity as u64).to_le_bytes());
+		file.write_all(&header)?;
+		file.set_len(HEADER_LEN + capacity as u64)?;
+		file.sync_all()?;
+
+		Ok(Journal{
+			file,
+			capacity,
+			policy,
+			index: VecDeque::new(),
+			tail: 0,
+			next_seq: 0,
+			unsynced: 0,
+		})
+	}
+
+	///Opens a file created by `create`, recovering the records which were completely written.
+	///A data region which is truncated by at most `MAX_TRUNCATION` bytes is extended again.
+	///
+	///Fails with `InvalidData` when the header is invalid, including a capacity which does not match the size of the file.
+	pub fn open<P: AsRef<Path>>(path: P,policy: FsyncPolicy) -> io::Result<Self>{
+		let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+		let mut header = [0;HEADER_LEN as usize];
+		file.read_exact(&mut header).map_err(|_| invalid_data("truncated header"))?;
+		if header[0..8] != MAGIC{
+			return Err(invalid_data("not a journal file"));
+		}
+		if read_u32_le(&header[8..12]) != VERSION{
+			return Err(invalid_data("unsupported version"));
+		}
+		//The capacity is untrusted, so it is checked against the size of the file before allocating or extending anything
+		let data_len = file.metadata()?.len().saturating_sub(HEADER_LEN);
+		let capacity = read_u64_le(&header[16..24]);
+		if capacity <= RECORD_HEADER_LEN as u64 || !capacity.is_multiple_of(ALIGN as u64) || capacity > data_len.saturating_add(MAX_TRUNCATION){
+			return Err(invalid_data("invalid capacity"));
+		}
+		let capacity = usize::try_from(capacity).map_err(|_| invalid_data("invalid capacity"))?;
+
+		let mut data = Vec::new();
+		(&mut file).take(capacity as u64).read_to_end(&mut data)?;
+		if data.len() < capacity{
+			data.resize(capacity,0);
+			file.set_len(HEADER_LEN + capacity as u64)?;
+		}
+
+		let (index,tail) = recover(&data);
+		let mut out = Journal{
+			file,
+			capacity,
+			policy,
+			next_seq: index.back().map_or(0,|entry| entry.seq + 1),
+			index,
+			tail,
+			unsynced: 0,
+		};
+		out.clear_stale(&data)?;
+		Ok(out)
+	}
+
+	///Overwrites the bytes of the data region which are not part of a record with zeros.
+	///Otherwise, an appended record with the same sequence number and size as a discarded one would continue the chain of the discarded records after it.
+	fn clear_stale(&mut self,data: &[u8]) -> io::Result<()>{
+		let mut live: Vec<(usize,usize)> = self.index.iter().map(|entry| (entry.offset,entry.end())).collect();
+		live.sort_unstable();
+		live.push((self.capacity,self.capacity));
+
+		let mut cleared = false;
+		let mut start = 0;
+		for (offset,end) in live{
+			if offset > start && data[start..offset].iter().any(|&b| b != 0){
+				self.file.seek(SeekFrom::Start(HEADER_LEN + start as u64))?;
+				self.file.write_all(&vec![0;offset - start])?;
+				cleared = true;
+			}
+			start = start.max(end);
+		}
+		if cleared{
+			self.file.sync_data()?;
+		}
+		Ok(())
+	}
+
+	///Returns the size of the data region in bytes.
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.capacity}
+
+	///Returns the length of the largest record which fits.
+	#[inline]
+	pub fn max_record_len(&self) -> usize{
+		(self.capacity - RECORD_HEADER_LEN).min(u32::MAX as usize)
+	}
+
+	///Returns the number of records.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.index.len()}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.index.is_empty()}
+
+	///Returns the sequence number of the oldest record.
+	#[inline]
+	pub fn oldest_seq(&self) -> Option<u64>{
+		self.index.front().map(|entry| entry.seq)
+	}
+
+	///Returns the sequence number of the most recent record.
+	#[inline]
+	pub fn newest_seq(&self) -> Option<u64>{
+		self.index.back().map(|entry| entry.seq)
+	}
+
+	///Appends a record, evicting the oldest records as needed, and returns its sequence number.
+	///
+	///Fails with `InvalidInput` when `payload.len() > self.max_record_len()`.
+	pub fn append(&mut self,payload: &[u8]) -> io::Result<u64>{
+		if payload.len() > self.max_record_len(){
+			return Err(invalid_input("record larger than the capacity"));
+		}
+		let size = record_size(payload.len());
+		let offset = if self.tail + size <= self.capacity{self.tail}else{0};
+
+		//Evict the records overlapping the written range, and the skipped end when wrapping around
+		while let Some(&front) = self.index.front(){
+			let overlaps = (front.offset < offset + size && front.end() > offset) || (offset != self.tail && front.end() > self.tail);
+			if !overlaps{break;}
+			self.index.pop_front();
+		}
+
+		let seq = self.next_seq;
+		let mut record = Vec::with_capacity(size);
+		record.extend_from_slice(&seq.to_le_bytes());
+		record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+		record.extend_from_slice(&checksum(seq,payload).to_le_bytes());
+		record.extend_from_slice(payload);
+		self.file.seek(SeekFrom::Start(HEADER_LEN + offset as u64))?;
+		self.file.write_all(&record)?;
+
+		self.index.push_back(Entry{offset,seq,len: payload.len()});
+		self.tail = offset + size;
+		self.next_seq += 1;
+		self.unsynced += 1;
+		match self.policy{
+			FsyncPolicy::Always => self.sync()?,
+			FsyncPolicy::Every(n) if self.unsynced >= n => self.sync()?,
+			_ => {},
+		}
+		Ok(seq)
+	}
+
+	///Flushes the written records to the storage device.
+	pub fn sync(&mut self) -> io::Result<()>{
+		self.file.sync_data()?;
+		self.unsynced = 0;
+		Ok(())
+	}
+
+	///Reads the payload of the record with the sequence number `seq`, if it is still present.
+	pub fn read(&mut self,seq: u64) -> io::Result<Option<Vec<u8>>>{
+		let entry = match self.oldest_seq(){
+			Some(oldest) if seq >= oldest && seq < self.next_seq => self.index[(seq - oldest) as usize],
+			_ => return Ok(None),
+		};
+		let mut payload = vec![0;entry.len];
+		self.file.seek(SeekFrom::Start(HEADER_LEN + (entry.offset + RECORD_HEADER_LEN) as u64))?;
+		self.file.read_exact(&mut payload)?;
+		Ok(Some(payload))
+	}
+
+	///Reads all records from the oldest to the most recent, with their sequence numbers.
+	pub fn read_all(&mut self) -> io::Result<Vec<(u64,Vec<u8>)>>{
+		let mut out = Vec::with_capacity(self.len());
+		for seq in self.oldest_seq().map_or(0..0,|oldest| oldest..self.next_seq){
+			if let Some(payload) = self.read(seq)?{
+				out.push((seq,payload));
+			}
+		}
+		Ok(out)
+	}
+}
+
+///Finds the records in the data region, returning them from the oldest to the most recent, and the offset after the most recent record.
+fn recover(data: &[u8]) -> (VecDeque<Entry>,usize){
+	//The records of the current lap start at 0
+	let recent = chain(data,0);
+	let tail = recent.last().map_or(0,Entry::end);
+
+	//The remaining records of the previous lap are somewhere after them
+	let mut previous: Option<Vec<Entry>> = None;
+	let mut offset = tail;
+	while offset + RECORD_HEADER_LEN <= data.len(){
+		let candidate = chain(data,offset);
+		match candidate.last(){
+			Some(last) => {
+				offset = last.end();
+				let follows = match recent.first(){
+					Some(first) => last.seq.checked_add(1) == Some(first.seq),
+					None => previous.as_ref().is_none_or(|previous| previous.last().unwrap().seq < last.seq),
+				};
+				if follows{
+					previous = Some(candidate);
+				}
+			},
+			None => offset += ALIGN,
+		}
+	}
+
+	match previous{
+		Some(previous) if recent.is_empty() => {
+			let tail = previous.last().unwrap().end();
+			(previous.into(),tail)
+		},
+		Some(mut previous) => {
+			previous.extend(recent);
+			(previous.into(),tail)
+		},
+		None => (recent.into(),tail),
+	}
+}
+
+///Returns the valid records with consecutive sequence numbers starting at `offset`.
+fn chain(data: &[u8],mut offset: usize) -> Vec<Entry>{
+	let mut out: Vec<Entry> = Vec::new();
+	while let Some(entry) = parse(data,offset){
+		if out.last().is_some_and(|last| last.seq.checked_add(1) != Some(entry.seq)){break;}
+		out.push(entry);
+		offset = entry.end();
+	}
+	out
+}
+
+///Returns the record at `offset` if it is valid.
+fn parse(data: &[u8],offset: usize) -> Option<Entry>{
+	let header = data.get(offset..offset + RECORD_HEADER_LEN)?;
+	let seq = read_u64_le(&header[0..8]);
+	let len = read_u32_le(&header[8..12]) as usize;
+	let payload = data.get(offset + RECORD_HEADER_LEN..)?.get(..len)?;
+	if read_u32_le(&header[12..16]) == checksum(seq,payload){
+		Some(Entry{offset,seq,len})
+	}else{
+		None
+	}
+}
+
+fn checksum(seq: u64,payload: &[u8]) -> u32{
+	let mut hasher = Hasher::new();
+	hasher.update(&seq.to_le_bytes());
+	hasher.update(&(payload.len() as u32).to_le_bytes());
+	hasher.update(payload);
+	hasher.finalize()
+}
+
+#[inline(always)]
+fn record_size(len: usize) -> usize{
+	(RECORD_HEADER_LEN + len).div_ceil(ALIGN) * ALIGN
+}
@@ -0,0 +1,192 @@
+use super::*;
+
+use std::fs;
+
+use test_util::temp_path;
+
+fn payload(seq: u64) -> Vec<u8>{
+	let len = (seq.wrapping_mul(0x9e37_79b9) >> 7) as usize % 40;
+	(0..len).map(|i| (seq as usize * 31 + i) as u8).collect()
+}
+
+struct XorShift(u32);
+impl XorShift{
+	fn next(&mut self) -> u32{
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 17;
+		self.0 ^= self.0 << 5;
+		self.0
+	}
+}
+
+///Checks that the recovered records are consecutive and intact, and that the journal is writable.
+fn check_recovered(path: &Path) -> Vec<u64>{
+	let mut j = Journal::open(path,FsyncPolicy::Never).unwrap();
+	let records = j.read_all().unwrap();
+	assert_eq!(records.len(),j.len());
+	for (i,&(seq,ref data)) in records.iter().enumerate(){
+		assert_eq!(seq,records[0].0 + i as u64);
+		assert_eq!(*data,payload(seq));
+	}
+
+	let seq = j.append(&payload(1000)).unwrap_or_else(|e| panic!("{}",e));
+	assert_eq!(j.read(seq).unwrap().unwrap(),payload(1000));
+	records.iter().map(|&(seq,_)| seq).collect()
+}
+
+#[test]
+fn test_append_wrap_reopen(){
+	let path = temp_path("journal-reopen");
+	let mut j = Journal::create(&path,100,FsyncPolicy::Always).unwrap();
+	assert_eq!(j.capacity(),96);
+	assert!(j.is_empty());
+	assert_eq!(j.append(b"first").unwrap(),0);
+	assert_eq!(j.append(&[7;30]).unwrap(),1);
+	assert_eq!(j.append(b"third").unwrap(),2);
+	assert_eq!(j.len(),3);
+
+	//Does not fit before the end, so the oldest records are overwritten
+	assert_eq!(j.append(&[8;20]).unwrap(),3);
+	assert_eq!(j.oldest_seq(),Some(2));
+	assert_eq!(j.newest_seq(),Some(3));
+	assert_eq!(j.read(0).unwrap(),None);
+	assert_eq!(j.read(2).unwrap().unwrap(),b"third");
+	assert_eq!(j.read(4).unwrap(),None);
+	let before = j.read_all().unwrap();
+	drop(j);
+
+	let mut j = Journal::open(&path,FsyncPolicy::Never).unwrap();
+	assert_eq!(j.read_all().unwrap(),before);
+	assert_eq!(j.append(b"fifth").unwrap(),4);
+	assert!(j.append(&[0;81]).is_err());
+	assert_eq!(j.max_record_len(),80);
+	j.append(&[1;80]).unwrap();
+	assert_eq!(j.read_all().unwrap(),[(5,vec![1;80])]);
+	drop(j);
+
+	assert_eq!(Journal::open(&path,FsyncPolicy::Never).unwrap().read_all().unwrap(),[(5,vec![1;80])]);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_fsync_every(){
+	let path = temp_path("journal-fsync");
+	let mut j = Journal::create(&path,256,FsyncPolicy::Every(3)).unwrap();
+	j.append(b"a").unwrap();
+	j.append(b"b").unwrap();
+	assert_eq!(j.unsynced,2);
+	j.append(b"c").unwrap();
+	assert_eq!(j.unsynced,0);
+	j.append(b"d").unwrap();
+	j.sync().unwrap();
+	assert_eq!(j.unsynced,0);
+	drop(j);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_invalid_header(){
+	let path = temp_path("journal-header");
+	drop(Journal::create(&path,64,FsyncPolicy::Never).unwrap());
+	let mut bytes = fs::read(&path).unwrap();
+	bytes[3] ^= 1;
+	fs::write(&path,&bytes).unwrap();
+	assert_eq!(Journal::open(&path,FsyncPolicy::Never).unwrap_err().kind(),io::ErrorKind::InvalidData);
+	fs::write(&path,&bytes[..10]).unwrap();
+	assert_eq!(Journal::open(&path,FsyncPolicy::Never).unwrap_err().kind(),io::ErrorKind::InvalidData);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_corrupted_capacity(){
+	let path = temp_path("journal-capacity");
+	drop(Journal::create(&path,64,FsyncPolicy::Never).unwrap());
+	let bytes = fs::read(&path).unwrap();
+	//Larger than the file by more than `MAX_TRUNCATION`
+	for &(byte,bit) in &[(18,1),(20,0),(21,7),(23,6)]{
+		let mut bad = bytes.clone();
+		bad[byte] ^= 1 << bit;
+		fs::write(&path,&bad).unwrap();
+		assert_eq!(Journal::open(&path,FsyncPolicy::Never).unwrap_err().kind(),io::ErrorKind::InvalidData);
+		assert_eq!(fs::metadata(&path).unwrap().len(),bytes.len() as u64);
+	}
+	fs::remove_file(&path).unwrap();
+}
+
+///Writes a wrapped journal, returning its bytes, its records and the offset after the most recent record.
+fn wrapped_journal(path: &Path,rng: &mut XorShift) -> (Vec<u8>,Vec<Entry>,usize){
+	let mut j = Journal::create(path,512,FsyncPolicy::Never).unwrap();
+	for seq in 0..(40 + rng.next() % 40) as u64{
+		assert_eq!(j.append(&payload(seq)).unwrap(),seq);
+	}
+	let entries = j.index.iter().cloned().collect();
+	let tail = j.tail;
+	drop(j);
+	(fs::read(path).unwrap(),entries,tail)
+}
+
+#[test]
+fn test_recover_truncated(){
+	let path = temp_path("journal-truncate");
+	let mut rng = XorShift(0x1234_5678);
+	for _ in 0..100{
+		let (bytes,entries,tail) = wrapped_journal(&path,&mut rng);
+		let cut = HEADER_LEN as usize + rng.next() as usize % (bytes.len() - HEADER_LEN as usize);
+		fs::write(&path,&bytes[..cut]).unwrap();
+
+		let recovered = check_recovered(&path);
+		let cut = cut - HEADER_LEN as usize;
+		for entry in entries.iter().filter(|entry| entry.offset < tail && (entry.end() <= cut || cut >= tail)){
+			assert!(recovered.contains(&entry.seq),"lost {} when truncated at {}",entry.seq,cut);
+		}
+		assert_eq!(fs::metadata(&path).unwrap().len(),bytes.len() as u64);
+	}
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_recover_corrupted(){
+	let path = temp_path("journal-corrupt");
+	let mut rng = XorShift(0x8765_4321);
+	for _ in 0..100{
+		let (mut bytes,entries,tail) = wrapped_journal(&path,&mut rng);
+		let at = HEADER_LEN as usize + rng.next() as usize % (bytes.len() - HEADER_LEN as usize);
+		bytes[at] ^= 1 << (rng.next() % 8);
+		fs::write(&path,&bytes).unwrap();
+
+		let recovered = check_recovered(&path);
+		let at = at - HEADER_LEN as usize;
+		for entry in entries.iter().filter(|entry| entry.offset < tail && (entry.end() <= at || at >= tail)){
+			assert!(recovered.contains(&entry.seq),"lost {} when corrupted at {}",entry.seq,at);
+		}
+		if entries.iter().all(|entry| at < entry.offset || at >= entry.end()){
+			assert_eq!(recovered,entries.iter().map(|entry| entry.seq).collect::<Vec<_>>());
+		}
+	}
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_recover_twice(){
+	let path = temp_path("journal-recover-twice");
+	let mut j = Journal::create(&path,512,FsyncPolicy::Never).unwrap();
+	for seq in 0..6{
+		j.append(&[seq as u8;8]).unwrap();
+	}
+	let offset = j.index[2].offset;
+	drop(j);
+
+	let mut bytes = fs::read(&path).unwrap();
+	bytes[HEADER_LEN as usize + offset + RECORD_HEADER_LEN] ^= 1;
+	fs::write(&path,&bytes).unwrap();
+	let mut j = Journal::open(&path,FsyncPolicy::Never).unwrap();
+	assert_eq!(j.read_all().unwrap(),[(0,vec![0;8]),(1,vec![1;8])]);
+
+	//Same sequence number and size as the discarded record
+	assert_eq!(j.append(&[99;8]).unwrap(),2);
+	drop(j);
+	let mut j = Journal::open(&path,FsyncPolicy::Never).unwrap();
+	assert_eq!(j.read_all().unwrap(),[(0,vec![0;8]),(1,vec![1;8]),(2,vec![99;8])]);
+	drop(j);
+	fs::remove_file(&path).unwrap();
+}
@@ -1,9 +1,61 @@
 #![feature(core)]
 
 extern crate core;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+#[cfg(feature = "pod")]
+extern crate bytemuck;
+#[cfg(feature = "journal")]
+extern crate crc32fast;
+#[cfg(feature = "libc")]
+extern crate libc;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate bincode;
+#[cfg(all(test,loom))]
+extern crate loom;
 
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod test_util;
+
+pub mod minmax;
+pub mod quantile;
+pub mod aggregate;
+pub mod segment;
+pub mod paired;
+pub mod spsc;
+pub mod broadcast;
+pub mod channel;
+pub mod async_channel;
+pub mod seqlock;
+pub mod bytes;
+pub mod reader;
+pub mod codec;
+pub mod scrollback;
+pub mod arena;
+pub mod tiered;
+pub mod observe;
+pub mod sequenced;
+pub mod timed;
+pub mod series;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(feature = "journal")]
+pub mod journal;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(all(feature = "vmem",target_os = "linux"))]
+pub mod vmem;
+#[cfg(all(feature = "shm",unix))]
+pub mod shm;
+
+mod sync;
+mod io_util;
 
 use core::iter::FromIterator;
 use core::marker::PhantomData;
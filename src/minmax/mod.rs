@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::VecDeque;
+use core::ops::{Deref,DerefMut};
+
+use CircularBuffer;
+
+///Which element to choose when several elements in the window compare as equal.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum TieBreak{
+	///Prefer the most recently queued element (the smallest logical index).
+	Newest,
+	///Prefer the oldest queued element (the largest logical index).
+	Oldest,
+}
+
+///Circular buffer keeping track of its minimum and maximum elements.
+///
+///Uses monotonic deques of sequence numbers, making `queue` amortized O(1) and the queries O(1).
+///Elements which are incomparable (e.g. NaN) give unspecified (but memory safe) results.
+#[derive(Clone,Debug)]
+pub struct RollingMinMax<T,L = Box<[T]>>{
+	buffer: CircularBuffer<T,L>,
+	///Sequence number of the most recently queued element.
+	newest: usize,
+	///Sequence numbers of the minimum candidates, from oldest to newest.
+	min: VecDeque<usize>,
+	///Sequence numbers of the maximum candidates, from oldest to newest.
+	max: VecDeque<usize>,
+	tie: TieBreak,
+}
+
+impl<T,L> RollingMinMax<T,L> where
+	T: PartialOrd,
+	L: Deref<Target=[T]>
+{
+	///Constructs the structure from an already filled buffer.
+	pub fn new(buffer: CircularBuffer<T,L>,tie: TieBreak) -> Self{
+		let len = buffer.len();
+		let mut out = RollingMinMax{
+			buffer,
+			newest: len - 1,
+			min: VecDeque::with_capacity(len),
+			max: VecDeque::with_capacity(len),
+			tie,
+		};
+		for seq in 0..len{
+			out.push_candidate(seq);
+		}
+		out
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<T,L>{&self.buffer}
+
+	///Deconstructs the structure into the underlying buffer.
+	#[inline(always)]
+	pub fn into_inner(self) -> CircularBuffer<T,L>{self.buffer}
+
+	///Returns the tie breaking rule in use.
+	#[inline(always)]
+	pub fn tie_break(&self) -> TieBreak{self.tie}
+
+	///Returns the logical index (usable with `CircularBuffer::get`) of the minimum element.
+	#[inline]
+	pub fn argmin(&self) -> usize{
+		self.index_of(self.min[0])
+	}
+
+	///Returns the logical index (usable with `CircularBuffer::get`) of the maximum element.
+	#[inline]
+	pub fn argmax(&self) -> usize{
+		self.index_of(self.max[0])
+	}
+
+	///Returns a reference to the minimum element.
+	#[inline]
+	pub fn min(&self) -> &T{
+		self.buffer.get(self.argmin())
+	}
+
+	///Returns a reference to the maximum element.
+	#[inline]
+	pub fn max(&self) -> &T{
+		self.buffer.get(self.argmax())
+	}
+
+	#[inline(always)]
+	fn index_of(&self,seq: usize) -> usize{
+		self.newest.wrapping_sub(seq)
+	}
+
+	///Adds the element with the sequence number `seq` as the newest candidate, removing the candidates that it dominates.
+	fn push_candidate(&mut self,seq: usize){
+		let newest = self.newest;
+		let tie = self.tie;
+		let buffer = &self.buffer;
+		let get = |seq: usize| buffer.get(newest.wrapping_sub(seq));
+		let elem = get(seq);
+
+		while let Some(&back) = self.min.back(){
+			let dominated = match tie{
+				TieBreak::Newest => *get(back) >= *elem,
+				TieBreak::Oldest => *get(back) >  *elem,
+			};
+			if !dominated{break;}
+			self.min.pop_back();
+		}
+		while let Some(&back) = self.max.back(){
+			let dominated = match tie{
+				TieBreak::Newest => *get(back) <= *elem,
+				TieBreak::Oldest => *get(back) <  *elem,
+			};
+			if !dominated{break;}
+			self.max.pop_back();
+		}
+		self.min.push_back(seq);
+		self.max.push_back(seq);
+	}
+}
+
+impl<T,L> RollingMinMax<T,L> where
+	T: PartialOrd,
+	L: DerefMut<Target=[T]>
+{
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,elem: T) -> T{
+		let out = self.buffer.queue(elem);
+		self.newest = self.newest.wrapping_add(1);
+
+		let len = self.buffer.len();
+		let newest = self.newest;
+		while let Some(&front) = self.min.front(){
+			if newest.wrapping_sub(front) < len{break;}
+			self.min.pop_front();
+		}
+		while let Some(&front) = self.max.front(){
+			if newest.wrapping_sub(front) < len{break;}
+			self.max.pop_front();
+		}
+		self.push_candidate(newest);
+		out
+	}
+}
+
+impl<T,L> From<CircularBuffer<T,L>> for RollingMinMax<T,L> where
+	T: PartialOrd,
+	L: Deref<Target=[T]>
+{
+	///Constructs the structure from an already filled buffer, preferring the newest element on ties.
+	#[inline]
+	fn from(buffer: CircularBuffer<T,L>) -> Self{
+		RollingMinMax::new(buffer,TieBreak::Newest)
+	}
+}
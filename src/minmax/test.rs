@@ -0,0 +1,86 @@
+use super::*;
+
+fn naive_argmin(l: &CircularBuffer<i32>,tie: TieBreak) -> usize{
+	let mut out = 0;
+	for i in 1..l.len(){
+		match tie{
+			TieBreak::Newest => if *l.get(i) <  *l.get(out){out = i;},
+			TieBreak::Oldest => if *l.get(i) <= *l.get(out){out = i;},
+		}
+	}
+	out
+}
+
+fn naive_argmax(l: &CircularBuffer<i32>,tie: TieBreak) -> usize{
+	let mut out = 0;
+	for i in 1..l.len(){
+		match tie{
+			TieBreak::Newest => if *l.get(i) >  *l.get(out){out = i;},
+			TieBreak::Oldest => if *l.get(i) >= *l.get(out){out = i;},
+		}
+	}
+	out
+}
+
+#[test]
+fn test_initial(){
+	let l = RollingMinMax::from(CircularBuffer::from(Box::new([3,1,4,1,5]) as Box<[i32]>));
+	assert_eq!(*l.min(),1);
+	assert_eq!(l.argmin(),1);
+	assert_eq!(*l.max(),5);
+	assert_eq!(l.argmax(),4);
+
+	let l = RollingMinMax::new(CircularBuffer::from(Box::new([3,1,4,1,5]) as Box<[i32]>),TieBreak::Oldest);
+	assert_eq!(l.argmin(),3);
+	assert_eq!(l.argmax(),4);
+}
+
+#[test]
+fn test_queue(){
+	let mut l = RollingMinMax::from(CircularBuffer::from(Box::new([2,2,2]) as Box<[i32]>));
+	assert_eq!(l.argmin(),0);
+
+	assert_eq!(l.queue(9),2);
+	assert_eq!(*l.max(),9);
+	assert_eq!(l.argmax(),0);
+	assert_eq!(*l.min(),2);
+	assert_eq!(l.argmin(),1);
+
+	assert_eq!(l.queue(0),2);
+	assert_eq!((*l.min(),l.argmin()),(0,0));
+	assert_eq!((*l.max(),l.argmax()),(9,1));
+
+	l.queue(5);
+	assert_eq!((*l.max(),l.argmax()),(9,2));
+
+	l.queue(5);
+	assert_eq!((*l.max(),l.argmax()),(5,0));
+	assert_eq!((*l.min(),l.argmin()),(0,2));
+
+	l.queue(6);
+	assert_eq!((*l.min(),l.argmin()),(5,1));
+}
+
+#[test]
+fn test_against_naive(){
+	for &tie in &[TieBreak::Newest,TieBreak::Oldest]{
+		for len in 1..8{
+			let mut l = RollingMinMax::new(CircularBuffer::from(vec![0;len].into_boxed_slice()),tie);
+			let mut state = 12345u32;
+			for _ in 0..200{
+				state = state.wrapping_mul(1103515245).wrapping_add(12345);
+				l.queue(((state >> 16) % 7) as i32);
+				assert_eq!(l.argmin(),naive_argmin(l.buffer(),tie));
+				assert_eq!(l.argmax(),naive_argmax(l.buffer(),tie));
+			}
+		}
+	}
+}
+
+#[test]
+fn test_partial_ord(){
+	let mut l = RollingMinMax::from(CircularBuffer::from(Box::new([0.5,-1.0]) as Box<[f64]>));
+	l.queue(2.5);
+	assert_eq!(*l.min(),0.5);
+	assert_eq!(*l.max(),2.5);
+}
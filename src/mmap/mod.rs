@@ -0,0 +1,189 @@
+//!Circular buffers persisted in memory mapped files.
+//!
+//!The file starts with a header of `HEADER_LEN` bytes, followed by the internal list of elements in their in-memory representation.
+//!The header fields are little-endian:
+//!
+//!| Offset | Size | Field                     |
+//!|--------|------|---------------------------|
+//!| 0      | 8    | `MAGIC`                   |
+//!| 8      | 4    | `VERSION`                 |
+//!| 12     | 4    | Element size in bytes     |
+//!| 16     | 8    | Capacity in elements      |
+//!| 24     | 8    | Internal index of `first` |
+
+#[cfg(test)]
+mod test;
+
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref,DerefMut};
+use std::fs::{File,OpenOptions};
+use std::io;
+use std::path::Path;
+
+use bytemuck::{self,Pod};
+use memmap2::MmapMut;
+
+use CircularBuffer;
+use io_util::{invalid_data,invalid_input,read_u32_le,read_u64_le};
+
+pub const MAGIC: [u8;8] = *b"FCBUFMAP";
+pub const VERSION: u32 = 1;
+///Size of the header, which is also the maximum supported alignment of elements.
+pub const HEADER_LEN: usize = 64;
+
+///Elements of a memory mapped file, usable as the list of a `CircularBuffer`.
+#[derive(Debug)]
+pub struct MmapSlice<T>{
+	map: MmapMut,
+	len: usize,
+	t: PhantomData<Box<[T]>>,
+}
+
+impl<T> MmapSlice<T>{
+	#[inline]
+	fn write_first(&mut self,first: usize){
+		self.map[24..32].copy_from_slice(&(first as u64).to_le_bytes());
+	}
+}
+
+impl<T: Pod> Deref for MmapSlice<T>{
+	type Target = [T];
+
+	#[inline]
+	fn deref(&self) -> &[T]{
+		bytemuck::cast_slice(&self.map[HEADER_LEN..HEADER_LEN + self.len * mem::size_of::<T>()])
+	}
+}
+
+impl<T: Pod> DerefMut for MmapSlice<T>{
+	#[inline]
+	fn deref_mut(&mut self) -> &mut [T]{
+		bytemuck::cast_slice_mut(&mut self.map[HEADER_LEN..HEADER_LEN + self.len * mem::size_of::<T>()])
+	}
+}
+
+///Circular buffer stored in a memory mapped file, restoring the same logical window when reopened.
+///
+///The elements are written directly to the mapping, while the position of the newest element is written to the header by `queue`, `queue_reversed` and `sync`.
+///The file is not portable between platforms of different endianness.
+#[derive(Debug)]
+pub struct MmapBuffer<T: Pod>{
+	buffer: CircularBuffer<T,MmapSlice<T>>,
+}
+
+impl<T: Pod> MmapBuffer<T>{
+	///Creates (or truncates) the file at `path`, filling the buffer with `capacity` copies of `fill`.
+	///
+	///Fails with `InvalidInput` when `capacity` is 0, or when the size or alignment of `T` is unsupported.
+	pub fn create<P: AsRef<Path>>(path: P,capacity: usize,fill: T) -> io::Result<Self>{
+		if capacity == 0{
+			return Err(invalid_input("capacity must not be 0"));
+		}
+		check_layout::<T>()?;
+
+		let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+		file.set_len((HEADER_LEN + capacity * mem::size_of::<T>()) as u64)?;
+		let mut map = map(&file)?;
+		map[0..8].copy_from_slice(&MAGIC);
+		map[8..12].copy_from_slice(&VERSION.to_le_bytes());
+		map[12..16].copy_from_slice(&(mem::size_of::<T>() as u32).to_le_bytes());
+		map[16..24].copy_from_slice(&(capacity as u64).to_le_bytes());
+
+		let mut list = MmapSlice{map,len: capacity,t: PhantomData};
+		list.write_first(0);
+		for elem in list.iter_mut(){
+			*elem = fill;
+		}
+		Ok(MmapBuffer{buffer: CircularBuffer::from(list)})
+	}
+
+	///Opens a file created by `create`, restoring the buffer as it was last synchronized.
+	///
+	///Fails with `InvalidData` when the header is invalid or does not match `T`.
+	pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self>{
+		check_layout::<T>()?;
+		let file = OpenOptions::new().read(true).write(true).open(path)?;
+		let map = map(&file)?;
+		if map.len() < HEADER_LEN || map[0..8] != MAGIC{
+			return Err(invalid_data("not a circular buffer file"));
+		}
+		if read_u32_le(&map[8..12]) != VERSION{
+			return Err(invalid_data("unsupported version"));
+		}
+		if read_u32_le(&map[12..16]) as usize != mem::size_of::<T>(){
+			return Err(invalid_data("element size mismatch"));
+		}
+		let capacity = read_u64_le(&map[16..24]) as usize;
+		let first = read_u64_le(&map[24..32]) as usize;
+		if capacity == 0 || capacity.checked_mul(mem::size_of::<T>()).and_then(|len| len.checked_add(HEADER_LEN)) != Some(map.len()){
+			return Err(invalid_data("capacity does not match the file size"));
+		}
+		if first >= capacity{
+			return Err(invalid_data("first out of range"));
+		}
+
+		let list = MmapSlice{map,len: capacity,t: PhantomData};
+		Ok(MmapBuffer{buffer: unsafe{CircularBuffer::from_raw_parts(list,first)}})
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<T,MmapSlice<T>>{&self.buffer}
+
+	///Returns the underlying buffer mutably.
+	///Changes of the position of the newest element are persisted on `sync`, `flush` or drop.
+	#[inline(always)]
+	pub fn buffer_mut(&mut self) -> &mut CircularBuffer<T,MmapSlice<T>>{&mut self.buffer}
+
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	#[inline]
+	pub fn queue(&mut self,elem: T) -> T{
+		let out = self.buffer.queue(elem);
+		self.sync();
+		out
+	}
+
+	///See `CircularBuffer::queue_reversed`.
+	#[inline]
+	pub fn queue_reversed(&mut self,elem: T) -> T{
+		let out = self.buffer.queue_reversed(elem);
+		self.sync();
+		out
+	}
+
+	///Writes the position of the newest element to the header.
+	#[inline]
+	pub fn sync(&mut self){
+		let first = self.buffer.first;
+		self.buffer.list.write_first(first);
+	}
+
+	///Synchronizes the header and flushes the mapping to the file.
+	pub fn flush(&mut self) -> io::Result<()>{
+		self.sync();
+		self.buffer.list.map.flush()
+	}
+}
+
+impl<T: Pod> Drop for MmapBuffer<T>{
+	#[inline]
+	fn drop(&mut self){
+		self.sync();
+	}
+}
+
+fn map(file: &File) -> io::Result<MmapMut>{
+	//The mapping is only unsound when the file is modified by others, which is documented as unsupported
+	unsafe{MmapMut::map_mut(file)}
+}
+
+fn check_layout<T>() -> io::Result<()>{
+	if mem::size_of::<T>() == 0 || mem::size_of::<T>() > u32::MAX as usize{
+		Err(invalid_input("unsupported element size"))
+	}else if mem::align_of::<T>() > HEADER_LEN{
+		Err(invalid_input("unsupported element alignment"))
+	}else{
+		Ok(())
+	}
+}
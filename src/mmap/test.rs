@@ -0,0 +1,56 @@
+use super::*;
+
+use std::fs;
+
+use test_util::temp_path;
+
+#[test]
+fn test_reopen_restores_window(){
+	let path = temp_path("reopen");
+	{
+		let mut b = MmapBuffer::create(&path,4,0u32).unwrap();
+		for i in 1..=6{
+			b.queue(i);
+		}
+		assert_eq!(b.buffer().iter().cloned().collect::<Vec<_>>(),[6,5,4,3]);
+		b.flush().unwrap();
+	}
+	{
+		let mut b = MmapBuffer::<u32>::open(&path).unwrap();
+		assert_eq!(b.buffer().iter().cloned().collect::<Vec<_>>(),[6,5,4,3]);
+		assert_eq!(b.queue(7),3);
+
+		//Persisted on drop
+		b.buffer_mut().set_first(1);
+	}
+	let b = MmapBuffer::<u32>::open(&path).unwrap();
+	assert_eq!(b.buffer().iter().cloned().collect::<Vec<_>>(),[6,5,4,7]);
+	drop(b);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_open_validates_header(){
+	let path = temp_path("validate");
+	drop(MmapBuffer::create(&path,3,0u64).unwrap());
+	assert_eq!(MmapBuffer::<u32>::open(&path).unwrap_err().kind(),io::ErrorKind::InvalidData);
+	assert!(MmapBuffer::<u64>::open(&path).is_ok());
+	assert!(MmapBuffer::<i64>::open(&path).is_ok());
+
+	let mut bytes = fs::read(&path).unwrap();
+	bytes[24] = 3;
+	fs::write(&path,&bytes).unwrap();
+	assert_eq!(MmapBuffer::<u64>::open(&path).unwrap_err().kind(),io::ErrorKind::InvalidData);
+
+	bytes[24] = 0;
+	bytes.pop();
+	fs::write(&path,&bytes).unwrap();
+	assert_eq!(MmapBuffer::<u64>::open(&path).unwrap_err().kind(),io::ErrorKind::InvalidData);
+
+	fs::write(&path,b"not a buffer").unwrap();
+	assert_eq!(MmapBuffer::<u64>::open(&path).unwrap_err().kind(),io::ErrorKind::InvalidData);
+	fs::remove_file(&path).unwrap();
+
+	assert_eq!(MmapBuffer::create(&path,0,0u8).unwrap_err().kind(),io::ErrorKind::InvalidInput);
+	let _ = fs::remove_file(&path);
+}
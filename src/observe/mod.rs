@@ -0,0 +1,218 @@
+//!Circular buffer notifying a listener of every element entering and leaving it.
+
+#[cfg(test)]
+mod test;
+
+use core::ops::{AddAssign,Deref,DerefMut,SubAssign};
+
+use CircularBuffer;
+
+///Receiver of the elements entering and leaving an `Observable` buffer.
+///
+///Indices are logical indices (0 is the most recently queued element).
+///When an element replaces another, the replaced element is evicted before the new one is inserted.
+pub trait Listener<T>{
+	///Called after `elem` entered the buffer at `index`.
+	#[allow(unused_variables)]
+	fn on_insert(&mut self,index: usize,elem: &T){}
+
+	///Called after `elem` left the buffer from `index`.
+	#[allow(unused_variables)]
+	fn on_evict(&mut self,index: usize,elem: &T){}
+}
+
+impl<T> Listener<T> for (){}
+
+macro_rules! impl_listener_tuple{
+	($($name: ident),*) => {
+		#[allow(non_snake_case)]
+		impl<T,$($name: Listener<T>),*> Listener<T> for ($($name,)*){
+			#[inline]
+			fn on_insert(&mut self,index: usize,elem: &T){
+				let ($(ref mut $name,)*) = *self;
+				$($name.on_insert(index,elem);)*
+			}
+
+			#[inline]
+			fn on_evict(&mut self,index: usize,elem: &T){
+				let ($(ref mut $name,)*) = *self;
+				$($name.on_evict(index,elem);)*
+			}
+		}
+	};
+}
+impl_listener_tuple!(A);
+impl_listener_tuple!(A,B);
+impl_listener_tuple!(A,B,C);
+impl_listener_tuple!(A,B,C,D);
+
+impl<T,Li: Listener<T> + ?Sized> Listener<T> for Box<Li>{
+	#[inline]
+	fn on_insert(&mut self,index: usize,elem: &T){(**self).on_insert(index,elem)}
+
+	#[inline]
+	fn on_evict(&mut self,index: usize,elem: &T){(**self).on_evict(index,elem)}
+}
+
+impl<T,Li: Listener<T>> Listener<T> for Vec<Li>{
+	fn on_insert(&mut self,index: usize,elem: &T){
+		for listener in self.iter_mut(){
+			listener.on_insert(index,elem);
+		}
+	}
+
+	fn on_evict(&mut self,index: usize,elem: &T){
+		for listener in self.iter_mut(){
+			listener.on_evict(index,elem);
+		}
+	}
+}
+
+///Listener counting the inserted and evicted elements.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct Counter{
+	inserted: u64,
+	evicted: u64,
+}
+
+impl Counter{
+	#[inline(always)]
+	pub fn inserted(&self) -> u64{self.inserted}
+
+	#[inline(always)]
+	pub fn evicted(&self) -> u64{self.evicted}
+}
+
+impl<T> Listener<T> for Counter{
+	#[inline]
+	fn on_insert(&mut self,_: usize,_: &T){self.inserted += 1;}
+
+	#[inline]
+	fn on_evict(&mut self,_: usize,_: &T){self.evicted += 1;}
+}
+
+///Listener keeping the sum of the elements in the buffer.
+///
+///For floating point numbers, rounding errors accumulate over time.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct RunningSum<S>{
+	sum: S,
+}
+
+impl<S> RunningSum<S>{
+	///Constructs the listener starting from the given sum, which is usually zero.
+	#[inline(always)]
+	pub fn new(sum: S) -> Self{RunningSum{sum}}
+
+	#[inline(always)]
+	pub fn sum(&self) -> &S{&self.sum}
+}
+
+impl<T,S> Listener<T> for RunningSum<S> where
+	S: for<'t> AddAssign<&'t T> + for<'t> SubAssign<&'t T>
+{
+	#[inline]
+	fn on_insert(&mut self,_: usize,elem: &T){self.sum += elem;}
+
+	#[inline]
+	fn on_evict(&mut self,_: usize,elem: &T){self.sum -= elem;}
+}
+
+///Listener calling closures.
+#[derive(Copy,Clone,Debug)]
+pub struct Callbacks<I,E>{
+	pub on_insert: I,
+	pub on_evict: E,
+}
+
+impl<T,I,E> Listener<T> for Callbacks<I,E> where
+	I: FnMut(usize,&T),
+	E: FnMut(usize,&T)
+{
+	#[inline]
+	fn on_insert(&mut self,index: usize,elem: &T){(self.on_insert)(index,elem)}
+
+	#[inline]
+	fn on_evict(&mut self,index: usize,elem: &T){(self.on_evict)(index,elem)}
+}
+
+///Circular buffer notifying a listener of the elements entering and leaving it through its mutating methods.
+#[derive(Clone,Debug)]
+pub struct Observable<T,Li,L = Box<[T]>>{
+	buffer: CircularBuffer<T,L>,
+	listener: Li,
+}
+
+impl<T,Li,L> Observable<T,Li,L> where
+	Li: Listener<T>,
+	L: Deref<Target=[T]>
+{
+	///Constructs the structure from an already filled buffer, inserting its elements into the listener from the oldest.
+	pub fn new(buffer: CircularBuffer<T,L>,mut listener: Li) -> Self{
+		for index in (0..buffer.len()).rev(){
+			listener.on_insert(index,buffer.get(index));
+		}
+		Observable{buffer,listener}
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<T,L>{&self.buffer}
+
+	#[inline(always)]
+	pub fn listener(&self) -> &Li{&self.listener}
+
+	///Returns the listener mutably. Replacing it does not notify the new listener of the existing elements.
+	#[inline(always)]
+	pub fn listener_mut(&mut self) -> &mut Li{&mut self.listener}
+
+	///Deconstructs the structure into the underlying buffer and the listener.
+	#[inline(always)]
+	pub fn into_inner(self) -> (CircularBuffer<T,L>,Li){(self.buffer,self.listener)}
+}
+
+impl<T,Li,L> Observable<T,Li,L> where
+	Li: Listener<T>,
+	L: DerefMut<Target=[T]>
+{
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,elem: T) -> T{
+		let out = self.buffer.queue(elem);
+		self.listener.on_evict(self.buffer.len() - 1,&out);
+		self.listener.on_insert(0,self.buffer.get(0));
+		out
+	}
+
+	///Replaces the most recently queued element, which becomes the oldest (See `CircularBuffer::queue_reversed`).
+	pub fn queue_reversed(&mut self,elem: T) -> T{
+		let out = self.buffer.queue_reversed(elem);
+		let oldest = self.buffer.len() - 1;
+		self.listener.on_evict(0,&out);
+		self.listener.on_insert(oldest,self.buffer.get(oldest));
+		out
+	}
+
+	///Replaces the most recently queued element (See `CircularBuffer::swap`).
+	#[inline]
+	pub fn swap(&mut self,elem: T) -> T{
+		self.swap_at(0,elem)
+	}
+
+	///Replaces the element at the given index (See `CircularBuffer::swap_at`).
+	pub fn swap_at(&mut self,index: usize,elem: T) -> T{
+		let index = index % self.buffer.len();
+		let out = self.buffer.swap_at(index,elem);
+		self.listener.on_evict(index,&out);
+		self.listener.on_insert(index,self.buffer.get(index));
+		out
+	}
+
+	///Modifies the element at the given index in place, notifying the listener of the old value being evicted and the new one inserted.
+	pub fn modify<R,F: FnOnce(&mut T) -> R>(&mut self,index: usize,f: F) -> R{
+		let index = index % self.buffer.len();
+		self.listener.on_evict(index,self.buffer.get(index));
+		let out = f(self.buffer.get_mut(index));
+		self.listener.on_insert(index,self.buffer.get(index));
+		out
+	}
+}
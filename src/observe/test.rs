@@ -0,0 +1,61 @@
+use super::*;
+
+use std::cell::RefCell;
+
+fn buffer() -> CircularBuffer<i64>{
+	CircularBuffer::from(Box::new([3,2,1i64]) as Box<[i64]>)
+}
+
+#[test]
+fn test_counter_and_sum(){
+	let mut o = Observable::new(buffer(),(Counter::default(),RunningSum::new(0i64)));
+	assert_eq!(o.listener().0.inserted(),3);
+	assert_eq!(*o.listener().1.sum(),6);
+
+	assert_eq!(o.queue(10),1);
+	assert_eq!(*o.listener().1.sum(),15);
+	assert_eq!(o.swap(20),10);
+	assert_eq!(*o.listener().1.sum(),25);
+	assert_eq!(o.swap_at(4,7),3);
+	assert_eq!(*o.listener().1.sum(),29);
+	assert_eq!(o.queue_reversed(-1),20);
+	assert_eq!(*o.listener().1.sum(),8);
+	o.modify(1,|x| *x *= 10);
+
+	let (buffer,(counter,sum)) = o.into_inner();
+	assert_eq!(*sum.sum(),buffer.iter().sum::<i64>());
+	assert_eq!(counter.inserted(),8);
+	assert_eq!(counter.evicted(),5);
+}
+
+#[test]
+fn test_callback_indices(){
+	let events = RefCell::new(Vec::new());
+	{
+		let listener = Callbacks{
+			on_insert: |index: usize,elem: &i64| events.borrow_mut().push(('+',index,*elem)),
+			on_evict: |index: usize,elem: &i64| events.borrow_mut().push(('-',index,*elem)),
+		};
+		let mut o = Observable::new(buffer(),Box::new(listener));
+		o.queue(4);
+		o.queue_reversed(5);
+		assert!(o.buffer().iter().eq(&[3,2,5]));
+	}
+	assert_eq!(events.into_inner(),[
+		('+',2,1),('+',1,2),('+',0,3),
+		('-',2,1),('+',0,4),
+		('-',0,4),('+',2,5),
+	]);
+}
+
+#[test]
+fn test_composed_listeners(){
+	let mut o = Observable::new(buffer(),vec![RunningSum::new(0),RunningSum::new(100)]);
+	let mut o2 = Observable::new(CircularBuffer::from(Box::new([0.5]) as Box<[f64]>),((),Counter::default()));
+	o2.queue(1.5);
+	assert_eq!(o2.listener().1.evicted(),1);
+
+	o.queue(7);
+	let sums: Vec<i64> = o.listener().iter().map(|sum| *sum.sum()).collect();
+	assert_eq!(sums,[12,112]);
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod test;
+
+use core::ops::{Deref,DerefMut};
+
+use CircularBuffer;
+
+///Two equally long circular buffers advanced together, keeping track of their cross-statistics.
+///
+///The means and the (co-)moments are updated in O(1) for each queued pair.
+///The statistics are for the population (divided by the length, not by one less than the length).
+#[derive(Clone,Debug)]
+pub struct PairedWindow<L = Box<[f64]>>{
+	xs: CircularBuffer<f64,L>,
+	ys: CircularBuffer<f64,L>,
+	mean_x: f64,
+	mean_y: f64,
+	///Sum of squared deviations from the mean of `xs`.
+	m_xx: f64,
+	///Sum of squared deviations from the mean of `ys`.
+	m_yy: f64,
+	///Sum of the products of the deviations from the means.
+	c_xy: f64,
+}
+
+impl<L> PairedWindow<L> where
+	L: Deref<Target=[f64]>
+{
+	///Constructs the structure from two already filled buffers.
+	///The elements at the same logical index form a pair.
+	///
+	///# Panics
+	///
+	///When the buffers are of different lengths.
+	pub fn new(xs: CircularBuffer<f64,L>,ys: CircularBuffer<f64,L>) -> Self{
+		assert_eq!(xs.len(),ys.len());
+		let mut out = PairedWindow{xs,ys,mean_x: 0.0,mean_y: 0.0,m_xx: 0.0,m_yy: 0.0,c_xy: 0.0};
+		out.recompute();
+		out
+	}
+
+	///Recomputes all statistics from scratch, discarding accumulated rounding errors.
+	pub fn recompute(&mut self){
+		let n = self.xs.len() as f64;
+		self.mean_x = self.xs.iter().sum::<f64>() / n;
+		self.mean_y = self.ys.iter().sum::<f64>() / n;
+		self.m_xx = 0.0;
+		self.m_yy = 0.0;
+		self.c_xy = 0.0;
+		for (x,y) in self.xs.iter().zip(self.ys.iter()){
+			let dx = x - self.mean_x;
+			let dy = y - self.mean_y;
+			self.m_xx += dx * dx;
+			self.m_yy += dy * dy;
+			self.c_xy += dx * dy;
+		}
+	}
+
+	///Returns the buffer of the first components.
+	#[inline(always)]
+	pub fn xs(&self) -> &CircularBuffer<f64,L>{&self.xs}
+
+	///Returns the buffer of the second components.
+	#[inline(always)]
+	pub fn ys(&self) -> &CircularBuffer<f64,L>{&self.ys}
+
+	///Deconstructs the structure into the two underlying buffers.
+	#[inline(always)]
+	pub fn into_inner(self) -> (CircularBuffer<f64,L>,CircularBuffer<f64,L>){(self.xs,self.ys)}
+
+	#[inline(always)]
+	pub fn mean_x(&self) -> f64{self.mean_x}
+
+	#[inline(always)]
+	pub fn mean_y(&self) -> f64{self.mean_y}
+
+	#[inline]
+	pub fn variance_x(&self) -> f64{self.m_xx / self.xs.len() as f64}
+
+	#[inline]
+	pub fn variance_y(&self) -> f64{self.m_yy / self.xs.len() as f64}
+
+	#[inline]
+	pub fn covariance(&self) -> f64{self.c_xy / self.xs.len() as f64}
+
+	///Returns the Pearson correlation coefficient.
+	///This is NaN when any of the components is constant.
+	#[inline]
+	pub fn correlation(&self) -> f64{
+		self.c_xy / (self.m_xx * self.m_yy).sqrt()
+	}
+
+	///Returns the slope of the least squares line fitting `y` as a function of `x`.
+	///This is NaN or infinite when `x` is constant.
+	#[inline]
+	pub fn slope(&self) -> f64{
+		self.c_xy / self.m_xx
+	}
+
+	///Returns the intercept of the least squares line fitting `y` as a function of `x`.
+	#[inline]
+	pub fn intercept(&self) -> f64{
+		self.mean_y - self.slope() * self.mean_x
+	}
+
+	///Returns the coefficient of determination of the least squares line.
+	#[inline]
+	pub fn r_squared(&self) -> f64{
+		let r = self.correlation();
+		r * r
+	}
+}
+
+impl<L> PairedWindow<L> where
+	L: DerefMut<Target=[f64]>
+{
+	///Enqueues the given pair, dequeuing and returning the oldest pair (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,x: f64,y: f64) -> (f64,f64){
+		let old_x = self.xs.queue(x);
+		let old_y = self.ys.queue(y);
+
+		let n = self.xs.len() as f64;
+		let mean_x = self.mean_x + (x - old_x) / n;
+		let mean_y = self.mean_y + (y - old_y) / n;
+		self.m_xx += (x - self.mean_x) * (x - mean_x) - (old_x - self.mean_x) * (old_x - mean_x);
+		self.m_yy += (y - self.mean_y) * (y - mean_y) - (old_y - self.mean_y) * (old_y - mean_y);
+		self.c_xy += (x - self.mean_x) * (y - mean_y) - (old_x - self.mean_x) * (old_y - mean_y);
+		self.mean_x = mean_x;
+		self.mean_y = mean_y;
+
+		(old_x,old_y)
+	}
+}
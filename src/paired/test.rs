@@ -0,0 +1,59 @@
+use super::*;
+
+fn window(xs: &[f64],ys: &[f64]) -> PairedWindow{
+	PairedWindow::new(CircularBuffer::from(xs.to_vec().into_boxed_slice()),CircularBuffer::from(ys.to_vec().into_boxed_slice()))
+}
+
+fn assert_close(a: f64,b: f64){
+	assert!((a - b).abs() <= 1e-9 * (1.0 + a.abs().max(b.abs())),"{} != {}",a,b);
+}
+
+fn assert_same(a: &PairedWindow,b: &PairedWindow){
+	assert_close(a.mean_x(),b.mean_x());
+	assert_close(a.mean_y(),b.mean_y());
+	assert_close(a.variance_x(),b.variance_x());
+	assert_close(a.variance_y(),b.variance_y());
+	assert_close(a.covariance(),b.covariance());
+	assert_close(a.correlation(),b.correlation());
+	assert_close(a.slope(),b.slope());
+	assert_close(a.intercept(),b.intercept());
+	assert_close(a.r_squared(),b.r_squared());
+}
+
+#[test]
+fn test_linear(){
+	let l = window(&[1.0,2.0,3.0,4.0],&[3.0,5.0,7.0,9.0]);
+	assert_close(l.mean_x(),2.5);
+	assert_close(l.mean_y(),6.0);
+	assert_close(l.variance_x(),1.25);
+	assert_close(l.covariance(),2.5);
+	assert_close(l.correlation(),1.0);
+	assert_close(l.slope(),2.0);
+	assert_close(l.intercept(),1.0);
+	assert_close(l.r_squared(),1.0);
+}
+
+#[test]
+#[should_panic]
+fn test_different_lengths(){
+	window(&[1.0,2.0],&[1.0]);
+}
+
+#[test]
+fn test_queue_against_recompute(){
+	let mut l = window(&[0.0;5],&[1.0,2.0,0.0,4.0,3.0]);
+	let mut state = 4242u32;
+	for _ in 0..500{
+		state = state.wrapping_mul(1103515245).wrapping_add(12345);
+		let x = ((state >> 16) % 100) as f64 / 10.0;
+		state = state.wrapping_mul(1103515245).wrapping_add(12345);
+		let y = x * -0.5 + ((state >> 16) % 100) as f64 / 50.0;
+
+		let old = (*l.xs().get(4),*l.ys().get(4));
+		assert_eq!(l.queue(x,y),old);
+
+		let mut expected = l.clone();
+		expected.recompute();
+		assert_same(&l,&expected);
+	}
+}
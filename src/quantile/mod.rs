@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod test;
+
+use core::cmp::Ordering;
+use core::ops::{Deref,DerefMut};
+
+use CircularBuffer;
+
+///Index of the non-existing node after the last node.
+const NIL: usize = usize::MAX;
+
+#[derive(Copy,Clone,Debug)]
+struct Link{
+	///Index of the next node on this level.
+	next: usize,
+	///Number of nodes on the lowest level that this link skips over (including the next node).
+	width: usize,
+}
+
+///Circular buffer keeping track of the order statistics (median, quantiles, ranks) of its elements.
+///
+///The elements are kept in an indexable skiplist where each node corresponds to a slot of the internal `list`.
+///Nodes are linked by their indices, so no values are copied.
+///`queue` takes expected O(log n) time, and so do all the queries.
+///
+///Elements which are incomparable (e.g. NaN) must not be queued.
+///Doing so gives unspecified results and may panic.
+#[derive(Clone,Debug)]
+pub struct RollingQuantile<T,L = Box<[T]>>{
+	buffer: CircularBuffer<T,L>,
+	///Sequence number of the most recently queued element.
+	newest: usize,
+	///Sequence number of the element in each slot, used to order equal elements.
+	seqs: Box<[usize]>,
+	///Links of each node. The nodes are the slots of the buffer followed by the head.
+	links: Box<[Vec<Link>]>,
+}
+
+impl<T,L> RollingQuantile<T,L> where
+	T: PartialOrd,
+	L: Deref<Target=[T]>
+{
+	///Constructs the structure from an already filled buffer.
+	pub fn new(buffer: CircularBuffer<T,L>) -> Self{
+		let len = buffer.len();
+		let levels = (usize::BITS - len.leading_zeros()) as usize;
+
+		//Deterministic xorshift generator for the node heights
+		let mut state = 0x2545_f491u32;
+		let mut links = Vec::with_capacity(len + 1);
+		for _ in 0..len{
+			state ^= state << 13;
+			state ^= state >> 17;
+			state ^= state << 5;
+			let height = ((state.trailing_ones() as usize) + 1).min(levels);
+			links.push(vec![Link{next: NIL,width: 0};height]);
+		}
+		links.push(vec![Link{next: NIL,width: 1};levels]);
+
+		let mut out = RollingQuantile{
+			newest: len - 1,
+			seqs: vec![0;len].into_boxed_slice(),
+			links: links.into_boxed_slice(),
+			buffer,
+		};
+		for index in 0..len{
+			let slot = out.buffer.internal_index(index);
+			out.seqs[slot] = len - 1 - index;
+			out.insert(slot);
+		}
+		out
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<T,L>{&self.buffer}
+
+	///Deconstructs the structure into the underlying buffer.
+	#[inline(always)]
+	pub fn into_inner(self) -> CircularBuffer<T,L>{self.buffer}
+
+	///Returns the element which would be at `index` if the buffer were sorted in ascending order.
+	///Equal elements are ordered from oldest to most recently queued.
+	///
+	///# Panics
+	///
+	///When `index >= self.buffer().len()`.
+	pub fn select(&self,index: usize) -> &T{
+		assert!(index < self.buffer.len());
+
+		let mut node = self.head();
+		let mut remaining = index + 1;
+		for level in (0..self.links[node].len()).rev(){
+			while self.links[node][level].next != NIL && self.links[node][level].width <= remaining{
+				remaining -= self.links[node][level].width;
+				node = self.links[node][level].next;
+			}
+		}
+		&self.buffer.list[node]
+	}
+
+	///Returns the `q`-quantile, using the nearest rank to `q * (len - 1)`.
+	///
+	///# Panics
+	///
+	///When `q` is not in the range `0.0..=1.0`.
+	pub fn quantile(&self,q: f64) -> &T{
+		assert!((0.0..=1.0).contains(&q));
+		self.select((q * (self.buffer.len() - 1) as f64).round() as usize)
+	}
+
+	///Returns the median, which is the lower one of the two middle elements when the length is even.
+	#[inline]
+	pub fn median(&self) -> &T{
+		self.select((self.buffer.len() - 1) / 2)
+	}
+
+	///Returns the number of elements strictly less than `elem`.
+	pub fn rank(&self,elem: &T) -> usize{
+		let mut node = self.head();
+		let mut rank = 0;
+		for level in (0..self.links[node].len()).rev(){
+			loop{
+				let link = self.links[node][level];
+				if link.next == NIL || self.buffer.list[link.next].partial_cmp(elem) != Some(Ordering::Less){break;}
+				rank += link.width;
+				node = link.next;
+			}
+		}
+		rank
+	}
+
+	#[inline(always)]
+	fn head(&self) -> usize{self.buffer.len()}
+
+	///Whether the slot `a` is ordered before the slot `b`.
+	fn less(&self,a: usize,b: usize) -> bool{
+		match self.buffer.list[a].partial_cmp(&self.buffer.list[b]){
+			Some(Ordering::Less) => true,
+			Some(Ordering::Greater) => false,
+			_ => self.newest.wrapping_sub(self.seqs[a]) > self.newest.wrapping_sub(self.seqs[b]),
+		}
+	}
+
+	///Finds the last node before `slot` on each level, and the number of nodes skipped over on each level.
+	fn predecessors(&self,slot: usize) -> (Vec<usize>,Vec<usize>){
+		let head = self.head();
+		let levels = self.links[head].len();
+		let mut chain = vec![head;levels];
+		let mut steps = vec![0;levels];
+
+		let mut node = head;
+		for level in (0..levels).rev(){
+			loop{
+				let link = self.links[node][level];
+				if link.next == NIL || !self.less(link.next,slot){break;}
+				steps[level] += link.width;
+				node = link.next;
+			}
+			chain[level] = node;
+		}
+		(chain,steps)
+	}
+
+	///Links the node of `slot` into the list.
+	fn insert(&mut self,slot: usize){
+		let (chain,steps_at_level) = self.predecessors(slot);
+
+		let height = self.links[slot].len();
+		let mut steps = 0;
+		for (level,&prev) in chain.iter().enumerate(){
+			if level < height{
+				let prev_link = self.links[prev][level];
+				self.links[slot][level] = Link{next: prev_link.next,width: prev_link.width - steps};
+				self.links[prev][level] = Link{next: slot,width: steps + 1};
+				steps += steps_at_level[level];
+			}else{
+				self.links[prev][level].width += 1;
+			}
+		}
+	}
+
+	///Unlinks the node of `slot` from the list.
+	fn remove(&mut self,slot: usize){
+		let (chain,_) = self.predecessors(slot);
+		debug_assert_eq!(self.links[chain[0]][0].next,slot);
+
+		let height = self.links[slot].len();
+		for (level,&prev) in chain.iter().enumerate(){
+			if level < height{
+				let link = self.links[slot][level];
+				self.links[prev][level].width += link.width;
+				self.links[prev][level].width -= 1;
+				self.links[prev][level].next = link.next;
+			}else{
+				self.links[prev][level].width -= 1;
+			}
+		}
+	}
+}
+
+impl<T,L> RollingQuantile<T,L> where
+	T: PartialOrd,
+	L: DerefMut<Target=[T]>
+{
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,elem: T) -> T{
+		let slot = self.buffer.internal_index_reversed(1);
+		self.remove(slot);
+
+		let out = self.buffer.queue(elem);
+		self.newest = self.newest.wrapping_add(1);
+		self.seqs[slot] = self.newest;
+		self.insert(slot);
+		out
+	}
+}
+
+impl<T,L> From<CircularBuffer<T,L>> for RollingQuantile<T,L> where
+	T: PartialOrd,
+	L: Deref<Target=[T]>
+{
+	#[inline]
+	fn from(buffer: CircularBuffer<T,L>) -> Self{
+		RollingQuantile::new(buffer)
+	}
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+fn sorted(l: &CircularBuffer<i32>) -> Vec<i32>{
+	let mut out: Vec<i32> = l.iter().cloned().collect();
+	out.sort();
+	out
+}
+
+#[test]
+fn test_select(){
+	let l = RollingQuantile::from(CircularBuffer::from(Box::new([3,1,4,1,5]) as Box<[i32]>));
+	assert_eq!(*l.select(0),1);
+	assert_eq!(*l.select(1),1);
+	assert_eq!(*l.select(2),3);
+	assert_eq!(*l.select(3),4);
+	assert_eq!(*l.select(4),5);
+	assert_eq!(*l.median(),3);
+}
+
+#[test]
+#[should_panic]
+fn test_select_out_of_range(){
+	let l = RollingQuantile::from(CircularBuffer::from(Box::new([3,1,4]) as Box<[i32]>));
+	l.select(3);
+}
+
+#[test]
+fn test_quantile(){
+	let l = RollingQuantile::from(CircularBuffer::from((0..101).rev().collect::<Vec<i32>>().into_boxed_slice()));
+	assert_eq!(*l.quantile(0.0),0);
+	assert_eq!(*l.quantile(0.5),50);
+	assert_eq!(*l.quantile(0.9),90);
+	assert_eq!(*l.quantile(0.99),99);
+	assert_eq!(*l.quantile(1.0),100);
+}
+
+#[test]
+fn test_rank(){
+	let mut l = RollingQuantile::from(CircularBuffer::from(Box::new([3,1,4,1]) as Box<[i32]>));
+	assert_eq!(l.rank(&0),0);
+	assert_eq!(l.rank(&1),0);
+	assert_eq!(l.rank(&2),2);
+	assert_eq!(l.rank(&4),3);
+	assert_eq!(l.rank(&9),4);
+
+	assert_eq!(l.queue(5),1);
+	assert_eq!(l.rank(&2),1);
+	assert_eq!(l.rank(&9),4);
+}
+
+#[test]
+fn test_queue_against_sorted(){
+	for len in 1..10{
+		let mut l = RollingQuantile::from(unsafe{CircularBuffer::from_raw_parts(vec![0;len].into_boxed_slice(),len / 2)});
+		let mut state = 98765u32;
+		for _ in 0..300{
+			state = state.wrapping_mul(1103515245).wrapping_add(12345);
+			l.queue(((state >> 16) % 13) as i32);
+
+			let expected = sorted(l.buffer());
+			for (i,x) in expected.iter().enumerate(){
+				assert_eq!(l.select(i),x);
+			}
+			for x in -1..14{
+				assert_eq!(l.rank(&x),expected.iter().filter(|&&y| y < x).count());
+			}
+		}
+	}
+}
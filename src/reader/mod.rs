@@ -0,0 +1,123 @@
+//!Buffered reader backed by a `ByteRing`, able to peek across the wrap around without compacting.
+
+#[cfg(test)]
+mod test;
+
+use std::io::{self,BufRead,Read};
+
+use bytes::ByteRing;
+
+///Buffered reader which refills into the free part of a ring instead of moving the unread bytes.
+///
+///`peek` can look ahead as far as the capacity, returning the bytes as two slices when they wrap around.
+pub struct RingReader<R>{
+	inner: R,
+	ring: ByteRing,
+}
+
+impl<R: Read> RingReader<R>{
+	///Constructs a reader with a capacity of 8 KiB.
+	#[inline]
+	pub fn new(inner: R) -> Self{
+		RingReader::with_capacity(8 * 1024,inner)
+	}
+
+	///Constructs a reader able to buffer `capacity` bytes.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	#[inline]
+	pub fn with_capacity(capacity: usize,inner: R) -> Self{
+		RingReader{inner,ring: ByteRing::new(capacity)}
+	}
+
+	#[inline(always)]
+	pub fn get_ref(&self) -> &R{&self.inner}
+
+	///Returns the underlying reader. Reading from it directly skips the buffered bytes.
+	#[inline(always)]
+	pub fn get_mut(&mut self) -> &mut R{&mut self.inner}
+
+	///Deconstructs the structure into the underlying reader, discarding the buffered bytes.
+	#[inline(always)]
+	pub fn into_inner(self) -> R{self.inner}
+
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.ring.capacity()}
+
+	///Returns the buffered bytes as two slices (See `ByteRing::contents`).
+	#[inline]
+	pub fn buffer(&self) -> (&[u8],&[u8]){self.ring.contents()}
+
+	///Reads once from the underlying reader into the free part of the ring.
+	///Returns the number of read bytes, where 0 means either end of file or a full ring.
+	pub fn refill(&mut self) -> io::Result<usize>{
+		if self.ring.free() == 0{
+			return Ok(0);
+		}
+		let count = loop{
+			match self.inner.read(self.ring.free_mut().0){
+				Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+				result => break result?,
+			}
+		};
+		self.ring.commit(count);
+		Ok(count)
+	}
+
+	///Returns the next `count` bytes without consuming them, reading until enough are buffered.
+	///The bytes are returned as two slices, where the second one is non-empty when they wrap around.
+	///Fewer bytes are returned only at end of file.
+	///
+	///Fails with `InvalidInput` when `count` is larger than the capacity.
+	pub fn peek(&mut self,count: usize) -> io::Result<(&[u8],&[u8])>{
+		if count > self.ring.capacity(){
+			return Err(io::Error::new(io::ErrorKind::InvalidInput,"peeking more than the capacity"));
+		}
+		while self.ring.len() < count{
+			if self.refill()? == 0{
+				break;
+			}
+		}
+		let len = count.min(self.ring.len());
+		Ok(self.ring.range(0,len))
+	}
+}
+
+impl<R: Read> Read for RingReader<R>{
+	fn read(&mut self,out: &mut [u8]) -> io::Result<usize>{
+		//Bypass the ring for large reads when nothing is buffered
+		if self.ring.is_empty() && out.len() >= self.ring.capacity(){
+			return self.inner.read(out);
+		}
+		let count = {
+			let available = self.fill_buf()?;
+			let count = available.len().min(out.len());
+			out[..count].copy_from_slice(&available[..count]);
+			count
+		};
+		self.ring.consume(count);
+		Ok(count)
+	}
+}
+
+impl<R: Read> BufRead for RingReader<R>{
+	///Returns the first contiguous part of the buffered bytes, refilling when empty.
+	fn fill_buf(&mut self) -> io::Result<&[u8]>{
+		if self.ring.is_empty(){
+			self.refill()?;
+		}
+		Ok(self.ring.contents().0)
+	}
+
+	///Consumes `count` buffered bytes, which may span both parts of the ring.
+	///
+	///# Panics
+	///
+	///When `count` is larger than the number of buffered bytes.
+	#[inline]
+	fn consume(&mut self,count: usize){
+		self.ring.consume(count);
+	}
+}
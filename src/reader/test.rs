@@ -0,0 +1,61 @@
+use super::*;
+
+///Reader returning at most `chunk` bytes per read.
+struct Chunked<'d>{
+	data: &'d [u8],
+	chunk: usize,
+}
+
+impl<'d> Read for Chunked<'d>{
+	fn read(&mut self,out: &mut [u8]) -> io::Result<usize>{
+		let count = self.chunk.min(out.len()).min(self.data.len());
+		out[..count].copy_from_slice(&self.data[..count]);
+		self.data = &self.data[count..];
+		Ok(count)
+	}
+}
+
+#[test]
+fn test_peek_wrapping(){
+	let mut r = RingReader::with_capacity(8,Chunked{data: b"0123456789abcdef",chunk: 3});
+	assert_eq!(r.peek(4).unwrap(),(&b"0123"[..],&b""[..]));
+	assert_eq!(r.buffer(),(&b"012345"[..],&b""[..]));
+
+	BufRead::consume(&mut r,5);
+	assert_eq!(r.peek(6).unwrap(),(&b"567"[..],&b"89a"[..]));
+	assert_eq!(r.peek(8).unwrap(),(&b"567"[..],&b"89abc"[..]));
+
+	BufRead::consume(&mut r,8);
+	assert_eq!(r.peek(8).unwrap(),(&b"def"[..],&b""[..]));
+	assert!(r.peek(9).is_err());
+}
+
+#[test]
+fn test_read_to_end(){
+	let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+	let mut r = RingReader::with_capacity(7,Chunked{data: &data,chunk: 5});
+	let mut out = Vec::new();
+	r.read_to_end(&mut out).unwrap();
+	assert_eq!(out,data);
+}
+
+#[test]
+fn test_lines(){
+	let r = RingReader::with_capacity(4,Chunked{data: b"first\nsecond line\n\nlast",chunk: 3});
+	let lines: Vec<String> = r.lines().map(|l| l.unwrap()).collect();
+	assert_eq!(lines,vec!["first","second line","","last"]);
+}
+
+#[test]
+fn test_mixed(){
+	let mut r = RingReader::with_capacity(5,Chunked{data: b"abcdefghij",chunk: 4});
+	let mut out = [0;2];
+	r.read_exact(&mut out).unwrap();
+	assert_eq!(&out,b"ab");
+	assert_eq!(r.peek(5).unwrap(),(&b"cde"[..],&b"fg"[..]));
+	assert_eq!(r.fill_buf().unwrap(),b"cde");
+	BufRead::consume(&mut r,3);
+	let mut rest = String::new();
+	r.read_to_string(&mut rest).unwrap();
+	assert_eq!(rest,"fghij");
+}
@@ -0,0 +1,290 @@
+//!Scrollback of text lines limited by the total number of bytes rather than by the number of lines.
+
+#[cfg(test)]
+mod test;
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::iter::Rev;
+use std::ops::Range;
+
+use bytes::ByteRing;
+
+///Lines of text kept in one byte ring, evicting whole oldest lines when the byte budget is exceeded.
+///
+///Each line is stored followed by its `\n`, so every completed line costs at least one byte of the budget.
+///The most recent line may be partial, in which case text is appended to it until a `\n` is pushed.
+///A line which alone exceeds the budget only keeps its end.
+///
+///Lines are indexed like `CircularBuffer`: index 0 is the most recent line.
+#[derive(Clone,Debug)]
+pub struct Scrollback{
+	text: ByteRing,
+	///Absolute byte position of the start of each line, from oldest to most recent.
+	starts: VecDeque<usize>,
+	///Absolute byte position of the end of the text, which is the number of bytes ever written.
+	written: usize,
+	///Whether the most recent line is partial.
+	partial: bool,
+	///Number of evicted lines.
+	evicted: u64,
+}
+
+impl Scrollback{
+	///Constructs an empty scrollback holding at most `budget` bytes of text.
+	///
+	///# Panics
+	///
+	///When `budget` is 0.
+	pub fn new(budget: usize) -> Self{
+		Scrollback{
+			text: ByteRing::new(budget),
+			starts: VecDeque::new(),
+			written: 0,
+			partial: false,
+			evicted: 0,
+		}
+	}
+
+	///Returns the maximum number of bytes held.
+	#[inline(always)]
+	pub fn budget(&self) -> usize{self.text.capacity()}
+
+	///Returns the number of bytes held, including the line endings.
+	#[inline(always)]
+	pub fn bytes(&self) -> usize{self.text.len()}
+
+	///Returns the number of lines, including a partial line.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.starts.len()}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.starts.is_empty()}
+
+	///Whether the most recent line has not been ended yet.
+	#[inline(always)]
+	pub fn is_partial(&self) -> bool{self.partial}
+
+	///Returns the number of lines evicted so far.
+	#[inline(always)]
+	pub fn evicted(&self) -> u64{self.evicted}
+
+	pub fn clear(&mut self){
+		self.text.clear();
+		self.starts.clear();
+		self.partial = false;
+	}
+
+	///Returns the line at `index`, where 0 is the most recent line.
+	///
+	///# Panics
+	///
+	///When `index >= self.len()`.
+	pub fn line(&self,index: usize) -> Line<'_>{
+		assert!(index < self.len());
+		self.line_at(self.len() - 1 - index)
+	}
+
+	///Returns an iterator over the lines from the most recent to the oldest.
+	#[inline]
+	pub fn iter(&self) -> Iter<'_>{
+		Iter{scrollback: self,range: 0..self.len()}
+	}
+
+	///Returns an iterator over the lines from the oldest to the most recent.
+	#[inline]
+	pub fn iter_oldest_first(&self) -> Rev<Iter<'_>>{
+		self.iter().rev()
+	}
+
+	///Appends the text, where each `\n` ends the current line.
+	pub fn push_str(&mut self,s: &str){
+		for (i,part) in s.split('\n').enumerate(){
+			if i > 0{
+				self.end_line();
+			}
+			if !part.is_empty(){
+				self.begin_line();
+				self.append(part.as_bytes());
+			}
+		}
+	}
+
+	///Appends the text followed by a line ending.
+	#[inline]
+	pub fn push_line(&mut self,s: &str){
+		self.push_str(s);
+		self.end_line();
+	}
+
+	///Ends the current line, pushing an empty line if the most recent line is already complete.
+	pub fn end_line(&mut self){
+		self.begin_line();
+		self.append(b"\n");
+		self.partial = false;
+	}
+
+	///Returns the line at the position `index` of `starts`.
+	fn line_at(&self,index: usize) -> Line<'_>{
+		let base = self.written.wrapping_sub(self.text.len());
+		let start = self.starts[index];
+		let end = match self.starts.get(index + 1){
+			Some(&next) => next.wrapping_sub(1),
+			None if self.partial => self.written,
+			None => self.written.wrapping_sub(1),
+		};
+		Line{parts: self.text.range(start.wrapping_sub(base),end.wrapping_sub(start))}
+	}
+
+	///Starts a new partial line unless the most recent line is partial.
+	#[inline]
+	fn begin_line(&mut self){
+		if !self.partial{
+			self.starts.push_back(self.written);
+			self.partial = true;
+		}
+	}
+
+	///Appends bytes to the partial line, evicting to stay within the budget.
+	fn append(&mut self,mut bytes: &[u8]){
+		if bytes.len() > self.budget(){
+			//Only the end of the line fits
+			let mut skip = bytes.len() - self.budget();
+			while skip < bytes.len() && is_continuation(bytes[skip]){
+				skip += 1;
+			}
+			self.evicted += (self.len() - 1) as u64;
+			self.text.clear();
+			self.written = self.written.wrapping_add(skip);
+			self.starts.clear();
+			self.starts.push_back(self.written);
+			bytes = &bytes[skip..];
+		}
+
+		while self.text.free() < bytes.len(){
+			if self.len() > 1{
+				let base = self.written.wrapping_sub(self.text.len());
+				self.text.consume(self.starts[1].wrapping_sub(base));
+				self.starts.pop_front();
+				self.evicted += 1;
+			}else{
+				//Remove the start of the partial line, which is the only line
+				self.text.consume(bytes.len() - self.text.free());
+				while !self.text.is_empty() && is_continuation(self.text.get(0)){
+					self.text.consume(1);
+				}
+				self.starts[0] = self.written.wrapping_sub(self.text.len());
+			}
+		}
+
+		self.text.push(bytes);
+		self.written = self.written.wrapping_add(bytes.len());
+	}
+}
+
+impl fmt::Write for Scrollback{
+	#[inline]
+	fn write_str(&mut self,s: &str) -> fmt::Result{
+		self.push_str(s);
+		Ok(())
+	}
+}
+
+impl<'s> IntoIterator for &'s Scrollback{
+	type Item = Line<'s>;
+	type IntoIter = Iter<'s>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter{self.iter()}
+}
+
+///Continuation bytes have the form 0b10xxxxxx.
+#[inline(always)]
+fn is_continuation(byte: u8) -> bool{
+	byte & 0b1100_0000 == 0b1000_0000
+}
+
+///Line of a `Scrollback`, without its line ending.
+///
+///The line is given as two byte slices because it may wrap around the end of the ring.
+///The slices are only valid UTF-8 when concatenated.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub struct Line<'s>{
+	parts: (&'s [u8],&'s [u8]),
+}
+
+impl<'s> Line<'s>{
+	#[inline(always)]
+	pub fn parts(&self) -> (&'s [u8],&'s [u8]){self.parts}
+
+	///Returns the length in bytes.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.parts.0.len() + self.parts.1.len()}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.len() == 0}
+
+	///Returns the text, which is only copied when the line wraps around.
+	pub fn to_str(&self) -> Cow<'s,str>{
+		match self.parts{
+			(a,[]) => String::from_utf8_lossy(a),
+			(a,b) => {
+				let mut bytes = Vec::with_capacity(a.len() + b.len());
+				bytes.extend_from_slice(a);
+				bytes.extend_from_slice(b);
+				Cow::Owned(String::from_utf8_lossy(&bytes).into_owned())
+			},
+		}
+	}
+}
+
+impl<'s> fmt::Display for Line<'s>{
+	#[inline]
+	fn fmt(&self,f: &mut fmt::Formatter) -> fmt::Result{
+		f.write_str(&self.to_str())
+	}
+}
+
+impl<'s> PartialEq<str> for Line<'s>{
+	fn eq(&self,other: &str) -> bool{
+		let other = other.as_bytes();
+		other.len() == self.len() && other[..self.parts.0.len()] == *self.parts.0 && other[self.parts.0.len()..] == *self.parts.1
+	}
+}
+
+impl<'s,'o> PartialEq<&'o str> for Line<'s>{
+	#[inline]
+	fn eq(&self,other: &&'o str) -> bool{*self == **other}
+}
+
+///Iterator over the lines of a `Scrollback`, from the most recent to the oldest.
+#[derive(Clone,Debug)]
+pub struct Iter<'s>{
+	scrollback: &'s Scrollback,
+	///Remaining positions in `starts`.
+	range: Range<usize>,
+}
+
+impl<'s> Iterator for Iter<'s>{
+	type Item = Line<'s>;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item>{
+		self.range.next_back().map(|index| self.scrollback.line_at(index))
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize,Option<usize>){
+		self.range.size_hint()
+	}
+}
+
+impl<'s> DoubleEndedIterator for Iter<'s>{
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item>{
+		self.range.next().map(|index| self.scrollback.line_at(index))
+	}
+}
+
+impl<'s> ExactSizeIterator for Iter<'s>{}
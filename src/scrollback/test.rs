@@ -0,0 +1,94 @@
+use super::*;
+
+fn lines(s: &Scrollback) -> Vec<String>{
+	s.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn test_evict_whole_lines(){
+	let mut s = Scrollback::new(10);
+	s.push_line("abc");
+	s.push_line("de");
+	s.push_str("fg");
+	assert!(s.is_partial());
+	assert_eq!(s.bytes(),9);
+	assert_eq!(lines(&s),["fg","de","abc"]);
+
+	s.push_str("h\ni");
+	assert_eq!(lines(&s),["i","fgh","de"]);
+	assert_eq!(s.evicted(),1);
+	assert_eq!(s.bytes(),8);
+
+	s.push_str("jk\n");
+	assert!(!s.is_partial());
+	assert_eq!(lines(&s),["ijk","fgh"]);
+	assert_eq!(s.evicted(),2);
+}
+
+#[test]
+fn test_wrapping_line(){
+	let mut s = Scrollback::new(8);
+	s.push_line("ab");
+	s.push_line("cd");
+	s.push_line("efg");
+	assert_eq!(lines(&s),["efg","cd"]);
+	let line = s.line(0);
+	assert_eq!(line.parts(),(&b"ef"[..],&b"g"[..]));
+	assert_eq!(line.len(),3);
+	assert!(matches!(line.to_str(),Cow::Owned(ref text) if text == "efg"));
+	assert!(matches!(s.line(1).to_str(),Cow::Borrowed("cd")));
+}
+
+#[test]
+fn test_long_line_keeps_end(){
+	let mut s = Scrollback::new(4);
+	s.push_line("x");
+	s.push_str("abcdefg");
+	assert_eq!(lines(&s),["defg"]);
+	assert_eq!(s.evicted(),1);
+	s.push_str("h");
+	assert_eq!(lines(&s),["efgh"]);
+
+	let mut s = Scrollback::new(4);
+	s.push_str("a\u{e9}\u{20ac}");
+	assert_eq!(lines(&s),["\u{20ac}"]);
+	s.push_str("b");
+	s.push_str("c");
+	assert_eq!(lines(&s),["bc"]);
+}
+
+#[test]
+fn test_empty_lines_cost_a_byte(){
+	let mut s = Scrollback::new(3);
+	for _ in 0..5{
+		s.end_line();
+	}
+	assert_eq!(s.len(),3);
+	assert_eq!(s.evicted(),2);
+	assert!(s.iter().all(|line| line.is_empty()));
+}
+
+#[test]
+fn test_iteration_orders(){
+	let mut s = Scrollback::new(64);
+	for word in &["one","two","three","four"]{
+		s.push_line(word);
+	}
+	assert_eq!(s.iter().len(),4);
+	assert_eq!(s.iter_oldest_first().map(|line| line.to_string()).collect::<Vec<_>>(),["one","two","three","four"]);
+	assert_eq!((&s).into_iter().map(|line| line.to_string()).collect::<Vec<_>>(),["four","three","two","one"]);
+
+	let mut iter = s.iter();
+	assert_eq!(iter.next().unwrap(),"four");
+	assert_eq!(iter.next_back().unwrap(),"one");
+	assert_eq!(iter.len(),2);
+	assert_eq!(iter.next().unwrap(),"three");
+	assert_eq!(iter.next_back().unwrap(),"two");
+	assert!(iter.next().is_none());
+
+	assert_eq!(s.line(1),"three");
+	s.clear();
+	assert!(s.is_empty());
+	s.push_str("again");
+	assert_eq!(lines(&s),["again"]);
+}
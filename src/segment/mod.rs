@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod test;
+
+use core::ops::{Deref,DerefMut,Range};
+
+use CircularBuffer;
+use aggregate::Monoid;
+
+///Circular buffer augmented with a segment tree for folding arbitrary logical ranges under a monoid.
+///
+///The leaves of the tree are the slots of the internal `list` itself, so rotating the buffer does not touch the tree.
+///Modifications of single elements (`queue`, `swap_at`, `swap_internal`, ..) take O(log n) time and so does `range_fold`.
+pub struct SegmentTreeBuffer<M: Monoid,L = Box<[<M as Monoid>::Value]>>{
+	buffer: CircularBuffer<M::Value,L>,
+	monoid: M,
+	///Inner nodes of an implicit binary tree, where node `i` has the children `2i` and `2i+1`.
+	///Nodes from `len` and upwards are the leaves, stored in the buffer. Node 0 is unused.
+	nodes: Vec<M::Value>,
+}
+
+impl<M,L> SegmentTreeBuffer<M,L> where
+	M: Monoid,
+	L: Deref<Target=[M::Value]>
+{
+	///Constructs the structure from an already filled buffer.
+	pub fn new(buffer: CircularBuffer<M::Value,L>,monoid: M) -> Self{
+		let len = buffer.len();
+		let mut out = SegmentTreeBuffer{
+			nodes: (0..len).map(|_| monoid.identity()).collect(),
+			buffer,
+			monoid,
+		};
+		for node in (1..len).rev(){
+			out.nodes[node] = out.monoid.combine(out.node(2*node),out.node(2*node + 1));
+		}
+		out
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<M::Value,L>{&self.buffer}
+
+	///Returns the monoid.
+	#[inline(always)]
+	pub fn monoid(&self) -> &M{&self.monoid}
+
+	///Deconstructs the structure into the underlying buffer and the monoid.
+	#[inline(always)]
+	pub fn into_inner(self) -> (CircularBuffer<M::Value,L>,M){(self.buffer,self.monoid)}
+
+	///Returns the fold of the elements in the given range of logical indices, from the most recently queued to the oldest.
+	///Smaller indices are more recently queued elements (relative to `queue`) (0 is the newest).
+	///
+	///# Panics
+	///
+	///When `range.start > range.end` or `range.end > self.buffer().len()`.
+	pub fn range_fold(&self,range: Range<usize>) -> M::Value{
+		let len = self.buffer.len();
+		assert!(range.start <= range.end && range.end <= len);
+
+		let count = range.end - range.start;
+		let start = self.buffer.internal_index(range.start);
+		if count == 0{
+			self.monoid.identity()
+		}else if start + count <= len{
+			self.internal_fold(start,start + count)
+		}else{
+			let first = self.internal_fold(start,len);
+			let second = self.internal_fold(0,start + count - len);
+			self.monoid.combine(&first,&second)
+		}
+	}
+
+	///Returns the fold of all elements, from the most recently queued to the oldest.
+	#[inline]
+	pub fn fold(&self) -> M::Value{
+		self.range_fold(0..self.buffer.len())
+	}
+
+	#[inline]
+	fn node(&self,node: usize) -> &M::Value{
+		let len = self.buffer.len();
+		if node >= len{
+			&self.buffer.list[node - len]
+		}else{
+			&self.nodes[node]
+		}
+	}
+
+	///Folds the slots in the given range of internal indices.
+	fn internal_fold(&self,start: usize,end: usize) -> M::Value{
+		let len = self.buffer.len();
+		let mut left = self.monoid.identity();
+		let mut right = self.monoid.identity();
+		let mut start = start + len;
+		let mut end = end + len;
+		while start < end{
+			if start & 1 == 1{
+				left = self.monoid.combine(&left,self.node(start));
+				start += 1;
+			}
+			if end & 1 == 1{
+				end -= 1;
+				right = self.monoid.combine(self.node(end),&right);
+			}
+			start >>= 1;
+			end >>= 1;
+		}
+		self.monoid.combine(&left,&right)
+	}
+
+	///Recomputes the ancestors of the slot at the given internal index.
+	fn update(&mut self,internal_index: usize){
+		let mut node = (internal_index + self.buffer.len()) >> 1;
+		while node > 0{
+			self.nodes[node] = self.monoid.combine(self.node(2*node),self.node(2*node + 1));
+			node >>= 1;
+		}
+	}
+}
+
+impl<M,L> SegmentTreeBuffer<M,L> where
+	M: Monoid,
+	L: DerefMut<Target=[M::Value]>
+{
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	pub fn queue(&mut self,elem: M::Value) -> M::Value{
+		let out = self.buffer.queue(elem);
+		let first = self.buffer.first;
+		self.update(first);
+		out
+	}
+
+	///See `CircularBuffer::queue_reversed`.
+	pub fn queue_reversed(&mut self,elem: M::Value) -> M::Value{
+		let first = self.buffer.first;
+		let out = self.buffer.queue_reversed(elem);
+		self.update(first);
+		out
+	}
+
+	///Swaps the most recently queued element (See `CircularBuffer::swap`).
+	pub fn swap(&mut self,elem: M::Value) -> M::Value{
+		let first = self.buffer.first;
+		let out = self.buffer.swap(elem);
+		self.update(first);
+		out
+	}
+
+	///Swaps the element at the given index with the specified new one (See `CircularBuffer::swap_at`).
+	pub fn swap_at(&mut self,index: usize,elem: M::Value) -> M::Value{
+		let i = self.buffer.internal_index(index);
+		let out = self.buffer.swap_at(index,elem);
+		self.update(i);
+		out
+	}
+
+	///Swaps the two elements at the given indices `a` and `b` (See `CircularBuffer::swap_internal`).
+	pub fn swap_internal(&mut self,a: usize,b: usize){
+		let ia = self.buffer.internal_index(a);
+		let ib = self.buffer.internal_index(b);
+		self.buffer.swap_internal(a,b);
+		self.update(ia);
+		self.update(ib);
+	}
+
+	///Sets the offset for the first element (See `CircularBuffer::set_first`).
+	///This does not need to modify the tree.
+	#[inline]
+	pub fn set_first(&mut self,index: usize){
+		self.buffer.set_first(index);
+	}
+
+	///Returns a mutable reference to the element at the given index (See `CircularBuffer::get_mut`).
+	///The tree is updated when the returned guard is dropped.
+	#[inline]
+	pub fn get_mut(&mut self,index: usize) -> ValueMut<'_,M,L>{
+		ValueMut{
+			internal_index: self.buffer.internal_index(index),
+			tree: self,
+		}
+	}
+}
+
+impl<M,L> Clone for SegmentTreeBuffer<M,L> where
+	M: Monoid + Clone,
+	M::Value: Clone,
+	L: Clone
+{
+	fn clone(&self) -> Self{
+		SegmentTreeBuffer{
+			buffer: self.buffer.clone(),
+			monoid: self.monoid.clone(),
+			nodes: self.nodes.clone(),
+		}
+	}
+}
+
+///Mutable reference to an element of a `SegmentTreeBuffer`, updating the tree when dropped.
+pub struct ValueMut<'t,M: Monoid + 't,L: DerefMut<Target=[M::Value]> + 't>{
+	tree: &'t mut SegmentTreeBuffer<M,L>,
+	internal_index: usize,
+}
+
+impl<'t,M,L> Deref for ValueMut<'t,M,L> where
+	M: Monoid,
+	L: DerefMut<Target=[M::Value]>
+{
+	type Target = M::Value;
+
+	#[inline]
+	fn deref(&self) -> &M::Value{
+		&self.tree.buffer.list[self.internal_index]
+	}
+}
+
+impl<'t,M,L> DerefMut for ValueMut<'t,M,L> where
+	M: Monoid,
+	L: DerefMut<Target=[M::Value]>
+{
+	#[inline]
+	fn deref_mut(&mut self) -> &mut M::Value{
+		&mut self.tree.buffer.list[self.internal_index]
+	}
+}
+
+impl<'t,M,L> Drop for ValueMut<'t,M,L> where
+	M: Monoid,
+	L: DerefMut<Target=[M::Value]>
+{
+	fn drop(&mut self){
+		self.tree.update(self.internal_index);
+	}
+}
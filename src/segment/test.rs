@@ -0,0 +1,71 @@
+use super::*;
+use test_util::{Concat,Sum,concat};
+
+fn naive(l: &CircularBuffer<String>,range: Range<usize>) -> String{
+	l.iter().skip(range.start).take(range.end - range.start).map(|s| &**s).collect()
+}
+
+fn check(l: &SegmentTreeBuffer<Concat>){
+	for a in 0..l.buffer().len() + 1{
+		for b in a..l.buffer().len() + 1{
+			assert_eq!(l.range_fold(a..b),naive(l.buffer(),a..b));
+		}
+	}
+}
+
+fn letters(len: usize) -> CircularBuffer<String>{
+	CircularBuffer::from((0..len).map(|i| ((b'a' + i as u8) as char).to_string()).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_range_fold(){
+	let l = SegmentTreeBuffer::new(CircularBuffer::from(Box::new([1,2,3,4,5u32]) as Box<[u32]>),Sum);
+	assert_eq!(l.range_fold(0..0),0);
+	assert_eq!(l.range_fold(0..5),15);
+	assert_eq!(l.range_fold(1..3),5);
+	assert_eq!(l.fold(),15);
+}
+
+#[test]
+#[should_panic]
+fn test_range_fold_out_of_range(){
+	let l = SegmentTreeBuffer::new(CircularBuffer::from(Box::new([1,2,3u32]) as Box<[u32]>),Sum);
+	l.range_fold(1..4);
+}
+
+#[test]
+fn test_wrap_around(){
+	for len in 1..9{
+		let mut l = SegmentTreeBuffer::new(letters(len),concat());
+		check(&l);
+		for (i,c) in "0123456789".chars().enumerate(){
+			l.queue(c.to_string());
+			check(&l);
+			l.set_first(i % 3);
+			check(&l);
+		}
+	}
+}
+
+#[test]
+fn test_modify(){
+	let mut l = SegmentTreeBuffer::new(letters(6),concat());
+	l.set_first(4);
+	check(&l);
+
+	assert_eq!(l.swap_at(1,"X".to_string()),"f");
+	check(&l);
+
+	l.swap_internal(0,3);
+	check(&l);
+
+	assert_eq!(l.swap("Y".to_string()),"b");
+	check(&l);
+
+	l.queue_reversed("Z".to_string());
+	check(&l);
+
+	l.get_mut(2).push('!');
+	check(&l);
+	assert_eq!(l.fold(),"Xae!cdZ");
+}
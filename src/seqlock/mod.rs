@@ -0,0 +1,198 @@
+//!Ring of `Copy` elements with a single writer that never waits,
+//!and readers taking consistent snapshots through a sequence lock.
+//!
+//!The writer makes the sequence number odd while queueing and even again when done.
+//!A reader copies the whole ring and retries when the sequence number was odd or has changed in between,
+//!so a snapshot is never torn. Readers can be starved by a writer which queues continuously.
+//!
+//!The protocol is implemented by `write` and `read` over any storage of slots, which `shm` shares.
+
+#[cfg(test)]
+mod test;
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{self,AtomicU64,Ordering};
+
+use CircularBuffer;
+
+///Sequence number and internal index of the most recently queued element, guarding the slots.
+pub(crate) struct Lock<'l>{
+	pub seq: &'l AtomicU64,
+	pub first: &'l AtomicU64,
+	pub len: u64,
+}
+
+impl<'l> Lock<'l>{
+	///Enqueues `elem` into the slot before `first`, returning the element which was in it.
+	///
+	///# Unsafety
+	///
+	///`slot` must return valid pointers for the indices less than `len`, and there must not be another writer.
+	pub unsafe fn write<T,F: Fn(u64) -> *mut T>(&self,slot: F,elem: T) -> T{
+		let seq = self.seq.load(Ordering::Relaxed);
+		self.seq.store(seq.wrapping_add(1),Ordering::Relaxed);
+		atomic::fence(Ordering::Release);
+
+		let first = (self.first.load(Ordering::Relaxed) + self.len - 1) % self.len;
+		let slot = slot(first);
+		//Only the writer modifies the slots, so reading is not racy
+		let out = ptr::read(slot);
+		ptr::write_volatile(slot,elem);
+		self.first.store(first,Ordering::Relaxed);
+
+		self.seq.store(seq.wrapping_add(2),Ordering::Release);
+		out
+	}
+
+	///Copies all elements into `out`, from the most recently queued to the oldest,
+	///returning the number of elements queued before when the writer did not interfere.
+	///A torn value may not be a valid `T`, which is why it is copied into uninitialized memory.
+	///
+	///# Unsafety
+	///
+	///`slot` must return valid pointers for the indices less than `len`.
+	pub unsafe fn read<T,F: Fn(u64) -> *const T>(&self,slot: F,out: &mut [MaybeUninit<T>]) -> Option<u64>{
+		let seq = self.seq.load(Ordering::Acquire);
+		if seq & 1 == 1{
+			return None;
+		}
+
+		let first = self.first.load(Ordering::Relaxed);
+		for (i,o) in out.iter_mut().enumerate(){
+			*o = ptr::read_volatile(slot((first + i as u64) % self.len) as *const MaybeUninit<T>);
+		}
+
+		atomic::fence(Ordering::Acquire);
+		if self.seq.load(Ordering::Relaxed) == seq{
+			Some(seq / 2)
+		}else{
+			None
+		}
+	}
+}
+
+struct Shared<T>{
+	seq: AtomicU64,
+	first: AtomicU64,
+	slots: Box<[UnsafeCell<T>]>,
+}
+
+impl<T> Shared<T>{
+	#[inline(always)]
+	fn lock(&self) -> Lock<'_>{
+		Lock{seq: &self.seq,first: &self.first,len: self.slots.len() as u64}
+	}
+}
+
+unsafe impl<T: Copy + Send> Sync for Shared<T>{}
+unsafe impl<T: Copy + Send> Send for Shared<T>{}
+
+///Splits an already filled buffer into the writing and the reading half of a shared ring.
+pub fn split<T: Copy>(buffer: CircularBuffer<T>) -> (Writer<T>,Reader<T>){
+	let (list,first) = buffer.into_raw_parts();
+	let shared = Arc::new(Shared{
+		seq: AtomicU64::new(0),
+		first: AtomicU64::new(first as u64),
+		slots: list.iter().map(|&x| UnsafeCell::new(x)).collect::<Vec<_>>().into_boxed_slice(),
+	});
+	(Writer{shared: shared.clone()},Reader{shared})
+}
+
+///The writing half of a shared ring.
+pub struct Writer<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T: Copy> Writer<T>{
+	///Returns the number of elements.
+	#[inline]
+	pub fn capacity(&self) -> usize{self.shared.slots.len()}
+
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	///This never waits for the readers.
+	pub fn queue(&mut self,elem: T) -> T{
+		let shared = &*self.shared;
+		unsafe{shared.lock().write(|i| shared.slots[i as usize].get(),elem)}
+	}
+
+	///Creates another reader.
+	#[inline]
+	pub fn reader(&self) -> Reader<T>{
+		Reader{shared: self.shared.clone()}
+	}
+}
+
+///A reading half of a shared ring. Cloning it creates another reader.
+pub struct Reader<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T: Copy> Reader<T>{
+	///Returns the number of elements.
+	#[inline]
+	pub fn capacity(&self) -> usize{self.shared.slots.len()}
+
+	///Copies all elements into `out`, returning the sequence number when the writer did not interfere.
+	fn read_into(&self,out: &mut [MaybeUninit<T>]) -> Option<usize>{
+		let shared = &*self.shared;
+		unsafe{shared.lock().read(|i| shared.slots[i as usize].get() as *const T,out)}.map(|seq| seq as usize)
+	}
+
+	///Tries to copy all elements into `out`, from the most recently queued to the oldest (the order of `CircularBuffer::iter`).
+	///Returns the sequence number of the snapshot, which is the number of elements queued before it,
+	///or `None` when the writer interfered. `out` is not modified when this fails.
+	///
+	///# Panics
+	///
+	///When `out.len() != self.capacity()`.
+	pub fn try_snapshot(&self,out: &mut [T]) -> Option<usize>{
+		assert_eq!(out.len(),self.capacity());
+		let mut scratch = vec![MaybeUninit::uninit();out.len()];
+		let seq = self.read_into(&mut scratch)?;
+		for (o,s) in out.iter_mut().zip(scratch){
+			*o = unsafe{s.assume_init()};
+		}
+		Some(seq)
+	}
+
+	///Copies all elements into `out`, from the most recently queued to the oldest, retrying until consistent.
+	///Returns the sequence number of the snapshot (See `try_snapshot`).
+	///
+	///# Panics
+	///
+	///When `out.len() != self.capacity()`.
+	pub fn snapshot(&self,out: &mut [T]) -> usize{
+		assert_eq!(out.len(),self.capacity());
+		let mut scratch = vec![MaybeUninit::uninit();out.len()];
+		let seq = self.read_retrying(&mut scratch);
+		for (o,s) in out.iter_mut().zip(scratch){
+			*o = unsafe{s.assume_init()};
+		}
+		seq
+	}
+
+	///Returns a consistent copy of the ring.
+	pub fn to_buffer(&self) -> CircularBuffer<T>{
+		let mut scratch = vec![MaybeUninit::uninit();self.capacity()];
+		self.read_retrying(&mut scratch);
+		CircularBuffer::from(scratch.into_iter().map(|s| unsafe{s.assume_init()}).collect::<Vec<T>>())
+	}
+
+	fn read_retrying(&self,out: &mut [MaybeUninit<T>]) -> usize{
+		loop{
+			if let Some(seq) = self.read_into(out){
+				return seq;
+			}
+			::std::thread::yield_now();
+		}
+	}
+}
+
+impl<T> Clone for Reader<T>{
+	fn clone(&self) -> Self{
+		Reader{shared: self.shared.clone()}
+	}
+}
@@ -0,0 +1,69 @@
+use super::*;
+use std::thread;
+
+#[test]
+fn test_snapshot(){
+	let (mut w,r) = split(CircularBuffer::from(Box::new([3,2,1u32]) as Box<[u32]>));
+	let mut out = [0;3];
+	assert_eq!(r.snapshot(&mut out),0);
+	assert_eq!(out,[3,2,1]);
+
+	assert_eq!(w.queue(4),1);
+	assert_eq!(w.queue(5),2);
+	assert_eq!(r.try_snapshot(&mut out),Some(2));
+	assert_eq!(out,[5,4,3]);
+
+	let b = w.reader().to_buffer();
+	assert_eq!(b.iter().cloned().collect::<Vec<_>>(),vec![5,4,3]);
+}
+
+#[test]
+#[should_panic]
+fn test_snapshot_wrong_length(){
+	let (_,r) = split(CircularBuffer::from(Box::new([3,2,1u32]) as Box<[u32]>));
+	r.snapshot(&mut [0;2]);
+}
+
+#[test]
+fn test_stress_torn_reads(){
+	const COUNT: u64 = 100_000;
+	const LEN: usize = 16;
+	let (mut w,r) = split(CircularBuffer::from(vec![[0u64;8];LEN].into_boxed_slice()));
+
+	let readers: Vec<_> = (0..3).map(|_|{
+		let r = r.clone();
+		thread::spawn(move ||{
+			let mut out = [[0u64;8];LEN];
+			let mut snapshots = 0;
+			let mut last_seq = 0;
+			loop{
+				let seq = match r.try_snapshot(&mut out){
+					Some(seq) => seq,
+					None => {thread::yield_now(); continue;},
+				};
+				assert!(seq >= last_seq);
+				last_seq = seq;
+				snapshots += 1;
+
+				//Every element must be whole, and the elements must be consecutive
+				for (i,x) in out.iter().enumerate(){
+					assert!(x.iter().all(|&y| y == x[0]),"torn element {:?}",x);
+					assert_eq!(x[0],(seq as u64).saturating_sub(i as u64));
+				}
+				if seq as u64 == COUNT{
+					return snapshots;
+				}
+			}
+		})
+	}).collect();
+
+	for i in 1..COUNT + 1{
+		w.queue([i;8]);
+		if i % 256 == 0{
+			thread::yield_now();
+		}
+	}
+	for reader in readers{
+		assert!(reader.join().unwrap() > 0);
+	}
+}
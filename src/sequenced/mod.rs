@@ -0,0 +1,130 @@
+//!Circular buffer addressing its elements by absolute sequence numbers, which do not shift when queueing.
+
+#[cfg(test)]
+mod test;
+
+use core::ops::{Deref,DerefMut};
+
+use CircularBuffer;
+
+///Circular buffer numbering its elements by a monotonically increasing 64 bit counter.
+///
+///Logical indices (0 is the most recently queued element) shift on every `queue`, while sequence numbers keep referring to the same element
+///until it is overwritten, after which lookups return `None`.
+#[derive(Clone,Debug)]
+pub struct Sequenced<T,L = Box<[T]>>{
+	buffer: CircularBuffer<T,L>,
+	///Sequence number of the most recently queued element.
+	newest: u64,
+}
+
+impl<T,L> Sequenced<T,L> where
+	L: Deref<Target=[T]>
+{
+	///Constructs the structure from an already filled buffer, numbering its elements from 0 for the oldest.
+	#[inline]
+	pub fn new(buffer: CircularBuffer<T,L>) -> Self{
+		let newest = buffer.len() as u64 - 1;
+		Sequenced{buffer,newest}
+	}
+
+	///Constructs the structure from an already filled buffer, with `newest` as the sequence number of its most recently queued element.
+	///This resumes the numbering of a previous structure.
+	///
+	///# Panics
+	///
+	///When `newest < buffer.len() - 1`, because the oldest element would have a negative sequence number.
+	#[inline]
+	pub fn with_newest_seq(buffer: CircularBuffer<T,L>,newest: u64) -> Self{
+		assert!(newest >= buffer.len() as u64 - 1);
+		Sequenced{buffer,newest}
+	}
+
+	///Returns the underlying buffer.
+	#[inline(always)]
+	pub fn buffer(&self) -> &CircularBuffer<T,L>{&self.buffer}
+
+	///Deconstructs the structure into the underlying buffer and the sequence number of its most recently queued element.
+	#[inline(always)]
+	pub fn into_inner(self) -> (CircularBuffer<T,L>,u64){(self.buffer,self.newest)}
+
+	///Returns the sequence number of the most recently queued element.
+	#[inline(always)]
+	pub fn newest_seq(&self) -> u64{self.newest}
+
+	///Returns the sequence number of the oldest element.
+	#[inline(always)]
+	pub fn oldest_seq(&self) -> u64{self.newest - (self.buffer.len() as u64 - 1)}
+
+	///Returns the sequence number that the next queued element gets.
+	#[inline(always)]
+	pub fn next_seq(&self) -> u64{self.newest + 1}
+
+	///Returns the logical index of the element with the sequence number `seq`, or `None` when it is overwritten or not queued yet.
+	#[inline]
+	pub fn seq_to_index(&self,seq: u64) -> Option<usize>{
+		if seq <= self.newest && seq >= self.oldest_seq(){
+			Some((self.newest - seq) as usize)
+		}else{
+			None
+		}
+	}
+
+	///Returns the sequence number of the element at the logical index `index`.
+	///
+	///# Panics
+	///
+	///When `index >= self.buffer().len()`.
+	#[inline]
+	pub fn index_to_seq(&self,index: usize) -> u64{
+		assert!(index < self.buffer.len());
+		self.newest - index as u64
+	}
+
+	///Returns the element with the sequence number `seq`, or `None` when it is overwritten or not queued yet.
+	#[inline]
+	pub fn get_by_seq(&self,seq: u64) -> Option<&T>{
+		self.seq_to_index(seq).map(|index| self.buffer.get(index))
+	}
+
+	///Returns an iterator over the elements with their sequence numbers, from the most recently queued to the oldest.
+	#[inline]
+	pub fn iter(&self) -> impl Iterator<Item=(u64,&T)>{
+		let newest = self.newest;
+		self.buffer.iter().enumerate().map(move |(index,elem)| (newest - index as u64,elem))
+	}
+}
+
+impl<T,L> Sequenced<T,L> where
+	L: DerefMut<Target=[T]>
+{
+	///Returns the element with the sequence number `seq` mutably, or `None` when it is overwritten or not queued yet.
+	#[inline]
+	pub fn get_by_seq_mut(&mut self,seq: u64) -> Option<&mut T>{
+		self.seq_to_index(seq).map(move |index| self.buffer.get_mut(index))
+	}
+
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	///The element gets the sequence number `self.next_seq()`.
+	#[inline]
+	pub fn queue(&mut self,elem: T) -> T{
+		self.newest += 1;
+		self.buffer.queue(elem)
+	}
+
+	///Enqueues the given element, dropping the oldest element, and returns the sequence number of the queued element.
+	#[inline]
+	pub fn push_seq(&mut self,elem: T) -> u64{
+		self.queue(elem);
+		self.newest
+	}
+}
+
+impl<T,L> From<CircularBuffer<T,L>> for Sequenced<T,L> where
+	L: Deref<Target=[T]>
+{
+	#[inline]
+	fn from(buffer: CircularBuffer<T,L>) -> Self{
+		Sequenced::new(buffer)
+	}
+}
@@ -0,0 +1,48 @@
+use super::*;
+
+fn buffer() -> CircularBuffer<char>{
+	CircularBuffer::from(Box::new(['c','b','a']) as Box<[char]>)
+}
+
+#[test]
+fn test_seq_lookup(){
+	let mut s = Sequenced::new(buffer());
+	assert_eq!((s.oldest_seq(),s.newest_seq()),(0,2));
+	assert_eq!(s.get_by_seq(0),Some(&'a'));
+	assert_eq!(s.get_by_seq(3),None);
+
+	assert_eq!(s.push_seq('d'),3);
+	assert_eq!(s.queue('e'),'b');
+	assert_eq!(s.next_seq(),5);
+	assert_eq!((s.oldest_seq(),s.newest_seq()),(2,4));
+	assert_eq!(s.get_by_seq(1),None);
+	assert_eq!(s.get_by_seq(2),Some(&'c'));
+	assert_eq!(s.get_by_seq(4),Some(&'e'));
+
+	*s.get_by_seq_mut(3).unwrap() = 'D';
+	assert!(s.get_by_seq_mut(0).is_none());
+	assert_eq!(s.iter().collect::<Vec<_>>(),[(4,&'e'),(3,&'D'),(2,&'c')]);
+}
+
+#[test]
+fn test_index_conversion(){
+	let mut s = Sequenced::with_newest_seq(buffer(),100);
+	s.queue('d');
+	for index in 0..3{
+		let seq = s.index_to_seq(index);
+		assert_eq!(s.seq_to_index(seq),Some(index));
+		assert_eq!(s.get_by_seq(seq),Some(s.buffer().get(index)));
+	}
+	assert_eq!(s.seq_to_index(98),None);
+	assert_eq!(s.seq_to_index(102),None);
+
+	let (buffer,newest) = s.into_inner();
+	let s = Sequenced::with_newest_seq(buffer,newest);
+	assert_eq!(s.index_to_seq(0),101);
+}
+
+#[test]
+#[should_panic]
+fn test_negative_oldest_seq(){
+	Sequenced::with_newest_seq(buffer(),1);
+}
@@ -0,0 +1,209 @@
+//!Time series lookups over circular buffers of `(timestamp,value)` samples.
+//!
+//!The samples must be queued in the order of their timestamps, so that timestamps never increase from the logical index 0 (the most recently queued sample) to the oldest.
+//!Samples are located by binary searching the logical indices, which maps through the wrap around of the internal list.
+
+#[cfg(test)]
+mod test;
+
+use core::ops::Deref;
+
+use CircularBuffer;
+
+///Timestamp of a sample, convertible to and from `f64` for interpolation.
+pub trait Timestamp: Copy + PartialOrd{
+	fn to_f64(self) -> f64;
+	fn from_f64(t: f64) -> Self;
+}
+
+macro_rules! impl_timestamp{
+	($($t: ty),*) => {$(
+		impl Timestamp for $t{
+			#[inline(always)]
+			fn to_f64(self) -> f64{self as f64}
+
+			#[inline(always)]
+			fn from_f64(t: f64) -> Self{t as $t}
+		}
+	)*};
+}
+impl_timestamp!(f32,f64,u32,u64,i32,i64);
+
+///Value which can be linearly interpolated.
+pub trait Lerp: Clone{
+	///Returns the value at `fraction` of the way from `self` to `other`, where the fraction may be outside of `0.0..=1.0` when extrapolating.
+	fn lerp(&self,other: &Self,fraction: f64) -> Self;
+}
+
+impl Lerp for f64{
+	#[inline(always)]
+	fn lerp(&self,other: &f64,fraction: f64) -> f64{self + (other - self) * fraction}
+}
+
+impl Lerp for f32{
+	#[inline(always)]
+	fn lerp(&self,other: &f32,fraction: f64) -> f32{self + (other - self) * fraction as f32}
+}
+
+impl<V: Lerp + Copy,const N: usize> Lerp for [V;N]{
+	fn lerp(&self,other: &Self,fraction: f64) -> Self{
+		let mut out = *self;
+		for (o,b) in out.iter_mut().zip(other.iter()){
+			*o = o.lerp(b,fraction);
+		}
+		out
+	}
+}
+
+///How to compute a value between two samples.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Interpolation{
+	///The value of the latest sample at or before the time.
+	Step,
+	///The straight line between the surrounding samples.
+	Linear,
+	///The value of the closest sample, preferring the earlier one when both are equally close.
+	Nearest,
+}
+
+///What to return for times before the oldest or after the most recent sample.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum OutOfRange{
+	///The value of the oldest or the most recent sample.
+	Clamp,
+	///The line through the two oldest or the two most recent samples for `Interpolation::Linear`, and the same as `Clamp` otherwise.
+	Extrapolate,
+	///`None`.
+	Reject,
+}
+
+///Where a time is relative to the samples.
+enum Bracket<'b,Ts,V>{
+	///Before the oldest sample.
+	Before(&'b (Ts,V)),
+	///At the time of a sample.
+	At(&'b (Ts,V)),
+	///Between an earlier and a later sample.
+	Between(&'b (Ts,V),&'b (Ts,V)),
+	///After the most recent sample.
+	After(&'b (Ts,V)),
+}
+
+impl<Ts,V,L> CircularBuffer<(Ts,V),L> where
+	Ts: Timestamp,
+	L: Deref<Target=[(Ts,V)]>
+{
+	///Binary searches for a sample with the timestamp `t` (See `slice::binary_search`).
+	///Returns the logical index of a matching sample,
+	///or the logical index where a sample with that timestamp would be if it were inserted in between.
+	pub fn search_time(&self,t: Ts) -> Result<usize,usize>{
+		let index = self.count_after(t);
+		if index < self.len() && self.get(index).0 == t{Ok(index)}else{Err(index)}
+	}
+
+	///Returns the number of leading logical indices with timestamps after `t`.
+	fn count_after(&self,t: Ts) -> usize{
+		let (mut low,mut high) = (0,self.len());
+		while low < high{
+			let mid = low + (high - low) / 2;
+			if self.get(mid).0 > t{
+				low = mid + 1;
+			}else{
+				high = mid;
+			}
+		}
+		low
+	}
+
+	fn bracket(&self,t: Ts) -> Bracket<'_,Ts,V>{
+		let index = self.count_after(t);
+		if index == self.len(){
+			return Bracket::Before(self.get(index - 1));
+		}
+		let before = self.get(index);
+		if before.0 == t{
+			Bracket::At(before)
+		}else if index == 0{
+			Bracket::After(before)
+		}else{
+			Bracket::Between(before,self.get(index - 1))
+		}
+	}
+}
+
+impl<Ts,V,L> CircularBuffer<(Ts,V),L> where
+	Ts: Timestamp,
+	V: Clone,
+	L: Deref<Target=[(Ts,V)]>
+{
+	///Returns the value of the latest sample at or before the time `t` (See `Interpolation::Step`).
+	pub fn step_at(&self,t: Ts,out_of_range: OutOfRange) -> Option<V>{
+		match self.bracket(t){
+			Bracket::At(sample) | Bracket::Between(sample,_) => Some(sample.1.clone()),
+			Bracket::Before(sample) | Bracket::After(sample) => clamp(sample,out_of_range),
+		}
+	}
+
+	///Returns the value of the sample closest to the time `t` (See `Interpolation::Nearest`).
+	pub fn nearest_at(&self,t: Ts,out_of_range: OutOfRange) -> Option<V>{
+		match self.bracket(t){
+			Bracket::At(sample) => Some(sample.1.clone()),
+			Bracket::Between(before,after) => {
+				let t = t.to_f64();
+				Some(if t - before.0.to_f64() <= after.0.to_f64() - t{before.1.clone()}else{after.1.clone()})
+			},
+			Bracket::Before(sample) | Bracket::After(sample) => clamp(sample,out_of_range),
+		}
+	}
+}
+
+impl<Ts,V,L> CircularBuffer<(Ts,V),L> where
+	Ts: Timestamp,
+	V: Lerp,
+	L: Deref<Target=[(Ts,V)]>
+{
+	///Returns the value on the straight line between the samples surrounding the time `t` (See `Interpolation::Linear`).
+	pub fn linear_at(&self,t: Ts,out_of_range: OutOfRange) -> Option<V>{
+		let len = self.len();
+		match self.bracket(t){
+			Bracket::At(sample) => Some(sample.1.clone()),
+			Bracket::Between(before,after) => Some(line(before,after,t)),
+			Bracket::Before(oldest) if out_of_range == OutOfRange::Extrapolate && len > 1 => Some(line(oldest,self.get(len - 2),t)),
+			Bracket::After(newest) if out_of_range == OutOfRange::Extrapolate && len > 1 => Some(line(self.get(1),newest,t)),
+			Bracket::Before(sample) | Bracket::After(sample) => clamp(sample,out_of_range),
+		}
+	}
+
+	///Returns the value at the time `t`.
+	pub fn value_at(&self,t: Ts,interpolation: Interpolation,out_of_range: OutOfRange) -> Option<V>{
+		match interpolation{
+			Interpolation::Step    => self.step_at(t,out_of_range),
+			Interpolation::Linear  => self.linear_at(t,out_of_range),
+			Interpolation::Nearest => self.nearest_at(t,out_of_range),
+		}
+	}
+
+	///Returns the values at the `count` times `start`, `start + period`, `start + 2 * period` and so on.
+	pub fn resample(&self,start: Ts,period: f64,count: usize,interpolation: Interpolation,out_of_range: OutOfRange) -> Vec<Option<V>>{
+		(0..count).map(|i| self.value_at(Ts::from_f64(start.to_f64() + period * i as f64),interpolation,out_of_range)).collect()
+	}
+}
+
+///Returns the value of the oldest or most recent sample for a time outside of the samples.
+#[inline]
+fn clamp<Ts,V: Clone>(sample: &(Ts,V),out_of_range: OutOfRange) -> Option<V>{
+	match out_of_range{
+		OutOfRange::Reject => None,
+		OutOfRange::Clamp | OutOfRange::Extrapolate => Some(sample.1.clone()),
+	}
+}
+
+///Returns the value at `t` on the line through the samples `a` and `b`, where `a` is the earlier one.
+fn line<Ts: Timestamp,V: Lerp>(a: &(Ts,V),b: &(Ts,V),t: Ts) -> V{
+	let span = b.0.to_f64() - a.0.to_f64();
+	if span == 0.0{
+		b.1.clone()
+	}else{
+		a.1.lerp(&b.1,(t.to_f64() - a.0.to_f64()) / span)
+	}
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+use self::Interpolation::*;
+
+///Samples at the times 0, 10, 20, 30 and 40, queued so that the internal list wraps around.
+fn samples() -> CircularBuffer<(u64,f64)>{
+	let mut b = CircularBuffer::from(Box::new([(0u64,0.0f64);5]) as Box<[(u64,f64)]>);
+	b.queue((0,0.0));
+	b.queue((0,1.0));
+	for (t,v) in [(10,5.0),(20,-5.0),(30,0.0),(40,10.0)]{
+		b.queue((t,v));
+	}
+	b
+}
+
+#[test]
+fn test_search_across_seam(){
+	let b = samples();
+	assert_ne!(b.internal_index(0),0);
+	assert_eq!(b.search_time(40),Ok(0));
+	assert_eq!(b.search_time(10),Ok(3));
+	assert_eq!(b.search_time(0),Ok(4));
+	assert_eq!(b.search_time(25),Err(2));
+	assert_eq!(b.search_time(50),Err(0));
+}
+
+#[test]
+fn test_interpolation(){
+	let b = samples();
+	let at = |t,interpolation| b.value_at(t,interpolation,OutOfRange::Reject);
+	assert_eq!(at(20,Linear),Some(-5.0));
+	assert_eq!(at(15,Linear),Some(0.0));
+	assert_eq!(at(35,Linear),Some(5.0));
+	assert_eq!(at(37,Step),Some(0.0));
+	assert_eq!(at(36,Nearest),Some(10.0));
+	assert_eq!(at(35,Nearest),Some(0.0));
+	assert_eq!(at(40,Nearest),Some(10.0));
+	assert_eq!(at(0,Step),Some(1.0));
+}
+
+#[test]
+fn test_out_of_range(){
+	let b = samples();
+	assert_eq!(b.value_at(41,Linear,OutOfRange::Reject),None);
+	assert_eq!(b.value_at(45,Linear,OutOfRange::Clamp),Some(10.0));
+	assert_eq!(b.value_at(45,Linear,OutOfRange::Extrapolate),Some(15.0));
+	assert_eq!(b.value_at(45,Step,OutOfRange::Extrapolate),Some(10.0));
+
+	let b: CircularBuffer<(f64,f64)> = CircularBuffer::from(Box::new([(3.0,2.0),(1.0,1.0)]) as Box<[(f64,f64)]>);
+	assert_eq!(b.value_at(0.0,Linear,OutOfRange::Reject),None);
+	assert_eq!(b.value_at(0.0,Nearest,OutOfRange::Clamp),Some(1.0));
+	assert_eq!(b.value_at(0.0,Linear,OutOfRange::Extrapolate),Some(0.5));
+
+	let single: CircularBuffer<(f64,f64)> = CircularBuffer::from(Box::new([(1.0,7.0)]) as Box<[(f64,f64)]>);
+	assert_eq!(single.value_at(5.0,Linear,OutOfRange::Extrapolate),Some(7.0));
+}
+
+#[test]
+fn test_resample(){
+	let b = samples();
+	assert_eq!(
+		b.resample(5,10.0,5,Linear,OutOfRange::Reject),
+		[Some(3.0),Some(0.0),Some(-2.5),Some(5.0),None]
+	);
+	assert_eq!(
+		b.resample(100,0.0,1,Step,OutOfRange::Clamp),
+		[Some(10.0)]
+	);
+
+	let b: CircularBuffer<(f64,[f32;2])> = CircularBuffer::from(Box::new([(1.0f64,[2.0f32,-2.0]),(0.0,[0.0,0.0])]) as Box<[(f64,[f32;2])]>);
+	assert_eq!(b.resample(0.0,0.25,3,Linear,OutOfRange::Reject),[Some([0.0,0.0]),Some([0.5,-0.5]),Some([1.0,-1.0])]);
+}
+
+#[test]
+fn test_without_lerp(){
+	let b: CircularBuffer<(u32,&str)> = CircularBuffer::from(Box::new([(20u32,"c"),(10,"b"),(0,"a")]) as Box<[(u32,&str)]>);
+	assert_eq!(b.search_time(10),Ok(1));
+	assert_eq!(b.step_at(19,OutOfRange::Reject),Some("b"));
+	assert_eq!(b.nearest_at(16,OutOfRange::Reject),Some("c"));
+	assert_eq!(b.nearest_at(25,OutOfRange::Clamp),Some("c"));
+	assert_eq!(b.step_at(25,OutOfRange::Reject),None);
+}
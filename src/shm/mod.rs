@@ -0,0 +1,260 @@
+//!Ring in POSIX shared memory, written by one process and read by others attaching to it by name.
+//!
+//!The shared memory starts with a header of `HEADER_LEN` bytes (magic number, capacity, element layout hash, sequence number and `first`),
+//!followed by the slots. The sequence number and `first` guard the slots with the protocol of `seqlock`.
+
+#[cfg(test)]
+mod test;
+
+use core::any;
+use core::marker::PhantomData;
+use core::mem::{self,MaybeUninit};
+use core::ptr;
+use core::slice;
+use std::ffi::CString;
+use std::io;
+use std::sync::atomic::{AtomicU64,Ordering};
+
+use bytemuck::Pod;
+
+use CircularBuffer;
+use io_util::{invalid_data,invalid_input};
+use seqlock::Lock;
+
+pub const MAGIC: u64 = u64::from_le_bytes(*b"FCBUFSHM");
+///Size of the header, which is also the maximum supported alignment of elements.
+pub const HEADER_LEN: usize = 64;
+
+#[repr(C)]
+struct Header{
+	///Written last when creating, so that a partially initialized header is never valid.
+	magic: AtomicU64,
+	capacity: u64,
+	layout: u64,
+	seq: AtomicU64,
+	first: AtomicU64,
+}
+
+///Hash of the size, alignment and name of `T`, identifying the element type between processes.
+pub fn layout_hash<T>() -> u64{
+	//FNV-1a
+	let mut hash = 0xcbf2_9ce4_8422_2325u64;
+	let size = (mem::size_of::<T>() as u64).to_le_bytes();
+	let align = (mem::align_of::<T>() as u64).to_le_bytes();
+	for &byte in size.iter().chain(align.iter()).chain(any::type_name::<T>().as_bytes()){
+		hash ^= byte as u64;
+		hash = hash.wrapping_mul(0x0100_0000_01b3);
+	}
+	hash
+}
+
+///Mapping of a shared memory object.
+struct Mapping{
+	ptr: *mut u8,
+	len: usize,
+}
+
+impl Mapping{
+	#[inline(always)]
+	fn header(&self) -> &Header{
+		unsafe{&*(self.ptr as *const Header)}
+	}
+
+	#[inline(always)]
+	fn slot<T>(&self,index: usize) -> *mut T{
+		unsafe{(self.ptr.add(HEADER_LEN) as *mut T).add(index)}
+	}
+
+	#[inline(always)]
+	fn lock(&self,capacity: usize) -> Lock<'_>{
+		let header = self.header();
+		Lock{seq: &header.seq,first: &header.first,len: capacity as u64}
+	}
+}
+
+impl Drop for Mapping{
+	fn drop(&mut self){
+		unsafe{libc::munmap(self.ptr as *mut libc::c_void,self.len);}
+	}
+}
+
+fn shm_name(name: &str) -> io::Result<CString>{
+	CString::new(name).map_err(|_| invalid_input("name contains a nul byte"))
+}
+
+///Maps the whole shared memory object of `fd`, closing it.
+unsafe fn map(fd: libc::c_int,len: usize,writable: bool) -> io::Result<Mapping>{
+	let prot = if writable{libc::PROT_READ | libc::PROT_WRITE}else{libc::PROT_READ};
+	let ptr = libc::mmap(ptr::null_mut(),len,prot,libc::MAP_SHARED,fd,0);
+	let error = io::Error::last_os_error();
+	libc::close(fd);
+	if ptr == libc::MAP_FAILED{
+		Err(error)
+	}else{
+		Ok(Mapping{ptr: ptr as *mut u8,len})
+	}
+}
+
+///Creates the shared memory object `name` (e.g. `/telemetry`) containing the elements of the buffer.
+///The object is removed when the writer is dropped.
+///
+///Fails with `AlreadyExists` when the name is in use, and with `InvalidInput` when the alignment of `T` is larger than `HEADER_LEN`.
+pub fn create<T: Pod>(name: &str,buffer: CircularBuffer<T>) -> io::Result<Writer<T>>{
+	if mem::align_of::<T>() > HEADER_LEN{
+		return Err(invalid_input("unsupported element alignment"));
+	}
+	let (list,first) = buffer.into_raw_parts();
+	let len = HEADER_LEN + list.len() * mem::size_of::<T>();
+
+	let c_name = shm_name(name)?;
+	let map = unsafe{
+		let fd = libc::shm_open(c_name.as_ptr(),libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,0o600 as libc::mode_t);
+		if fd < 0{
+			return Err(io::Error::last_os_error());
+		}
+		if libc::ftruncate(fd,len as libc::off_t) != 0{
+			let error = io::Error::last_os_error();
+			libc::close(fd);
+			libc::shm_unlink(c_name.as_ptr());
+			return Err(error);
+		}
+		match map(fd,len,true){
+			Ok(map) => map,
+			Err(error) => {
+				libc::shm_unlink(c_name.as_ptr());
+				return Err(error);
+			},
+		}
+	};
+
+	unsafe{
+		ptr::copy_nonoverlapping(list.as_ptr(),map.slot::<T>(0),list.len());
+		let header = map.ptr as *mut Header;
+		(*header).capacity = list.len() as u64;
+		(*header).layout = layout_hash::<T>();
+		(*header).seq = AtomicU64::new(0);
+		(*header).first = AtomicU64::new(first as u64);
+	}
+	map.header().magic.store(MAGIC,Ordering::Release);
+
+	Ok(Writer{map,capacity: list.len(),name: c_name,t: PhantomData})
+}
+
+///Attaches to the shared memory object `name` created by `create`, for reading.
+///
+///Fails with `NotFound` when there is no such object, and with `InvalidData` when it is not a ring of `T`.
+pub fn attach<T: Pod>(name: &str) -> io::Result<Reader<T>>{
+	let c_name = shm_name(name)?;
+	let map = unsafe{
+		let fd = libc::shm_open(c_name.as_ptr(),libc::O_RDONLY,0);
+		if fd < 0{
+			return Err(io::Error::last_os_error());
+		}
+		let mut stat: libc::stat = mem::zeroed();
+		if libc::fstat(fd,&mut stat) != 0{
+			let error = io::Error::last_os_error();
+			libc::close(fd);
+			return Err(error);
+		}
+		let len = stat.st_size as usize;
+		if len < HEADER_LEN{
+			libc::close(fd);
+			return Err(invalid_data("not a shared ring"));
+		}
+		map(fd,len,false)?
+	};
+
+	let header = map.header();
+	if header.magic.load(Ordering::Acquire) != MAGIC{
+		return Err(invalid_data("not a shared ring"));
+	}
+	if header.layout != layout_hash::<T>(){
+		return Err(invalid_data("element layout mismatch"));
+	}
+	let capacity = header.capacity as usize;
+	if capacity == 0 || capacity.checked_mul(mem::size_of::<T>()).and_then(|len| len.checked_add(HEADER_LEN)) != Some(map.len){
+		return Err(invalid_data("capacity does not match the size"));
+	}
+	Ok(Reader{map,capacity,t: PhantomData})
+}
+
+///The writing side of a shared ring.
+pub struct Writer<T>{
+	map: Mapping,
+	capacity: usize,
+	name: CString,
+	t: PhantomData<Box<[T]>>,
+}
+
+unsafe impl<T: Send> Send for Writer<T>{}
+
+impl<T: Pod> Writer<T>{
+	///Returns the number of elements.
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.capacity}
+
+	///Enqueues the given element, dequeuing and returning the oldest element (See `CircularBuffer::queue`).
+	///This never waits for the readers.
+	pub fn queue(&mut self,elem: T) -> T{
+		let map = &self.map;
+		unsafe{map.lock(self.capacity).write(|i| map.slot::<T>(i as usize),elem)}
+	}
+}
+
+impl<T> Drop for Writer<T>{
+	fn drop(&mut self){
+		unsafe{libc::shm_unlink(self.name.as_ptr());}
+	}
+}
+
+///A read-only attachment to a shared ring.
+pub struct Reader<T>{
+	map: Mapping,
+	capacity: usize,
+	t: PhantomData<Box<[T]>>,
+}
+
+unsafe impl<T: Send> Send for Reader<T>{}
+
+impl<T: Pod> Reader<T>{
+	///Returns the number of elements.
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.capacity}
+
+	///Tries to copy all elements into `out`, from the most recently queued to the oldest (the order of `CircularBuffer::iter`).
+	///Returns the sequence number of the snapshot, which is the number of elements queued before it,
+	///or `None` when the writer interfered, in which case `out` contains unspecified elements.
+	///
+	///# Panics
+	///
+	///When `out.len() != self.capacity()`.
+	pub fn try_snapshot(&self,out: &mut [T]) -> Option<u64>{
+		assert_eq!(out.len(),self.capacity);
+		let map = &self.map;
+		//Torn elements are still valid, because they are plain old data
+		let out = unsafe{slice::from_raw_parts_mut(out.as_mut_ptr() as *mut MaybeUninit<T>,out.len())};
+		unsafe{map.lock(self.capacity).read(|i| map.slot::<T>(i as usize) as *const T,out)}
+	}
+
+	///Copies all elements into `out`, from the most recently queued to the oldest, retrying until consistent.
+	///Returns the sequence number of the snapshot (See `try_snapshot`).
+	///
+	///# Panics
+	///
+	///When `out.len() != self.capacity()`.
+	pub fn snapshot(&self,out: &mut [T]) -> u64{
+		loop{
+			if let Some(seq) = self.try_snapshot(out){
+				return seq;
+			}
+			::std::thread::yield_now();
+		}
+	}
+
+	///Returns a consistent copy of the ring.
+	pub fn to_buffer(&self) -> CircularBuffer<T>{
+		let mut out = vec![T::zeroed();self.capacity].into_boxed_slice();
+		self.snapshot(&mut out);
+		CircularBuffer::from(out)
+	}
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+use std::process;
+use std::thread;
+
+fn name(test: &str) -> String{
+	format!("/fixed_circular_buffer-{}-{}",process::id(),test)
+}
+
+#[test]
+fn test_queue_and_snapshot(){
+	let name = name("queue");
+	let mut w = create(&name,CircularBuffer::from(Box::new([0,0,0u32]) as Box<[u32]>)).unwrap();
+	let r = attach::<u32>(&name).unwrap();
+	assert_eq!(r.capacity(),3);
+
+	let mut out = [9;3];
+	assert_eq!(r.try_snapshot(&mut out),Some(0));
+	assert_eq!(out,[0,0,0]);
+
+	assert_eq!(w.queue(1),0);
+	w.queue(2);
+	w.queue(3);
+	assert_eq!(w.queue(4),1);
+	assert_eq!(r.snapshot(&mut out),4);
+	assert_eq!(out,[4,3,2]);
+	assert!(r.to_buffer().iter().cloned().eq([4,3,2]));
+
+	assert_eq!(create(&name,CircularBuffer::from(Box::new([0u32]) as Box<[u32]>)).err().unwrap().kind(),io::ErrorKind::AlreadyExists);
+
+	//Readers stay valid after the name is removed
+	drop(w);
+	assert_eq!(r.snapshot(&mut out),4);
+	assert_eq!(attach::<u32>(&name).err().unwrap().kind(),io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_layout_mismatch(){
+	let name = name("layout");
+	let _w = create(&name,CircularBuffer::from(Box::new([0,0u64]) as Box<[u64]>)).unwrap();
+	assert_eq!(attach::<u32>(&name).err().unwrap().kind(),io::ErrorKind::InvalidData);
+	assert_eq!(attach::<i64>(&name).err().unwrap().kind(),io::ErrorKind::InvalidData);
+	assert!(attach::<u64>(&name).is_ok());
+	assert_ne!(layout_hash::<[u8;8]>(),layout_hash::<u64>());
+}
+
+#[derive(Copy,Clone)]
+#[repr(C,align(128))]
+struct Overaligned([u8;128]);
+unsafe impl bytemuck::Zeroable for Overaligned{}
+unsafe impl Pod for Overaligned{}
+
+#[test]
+fn test_unsupported_alignment(){
+	let name = name("align");
+	let buffer = CircularBuffer::from(Box::new([Overaligned([0;128])]) as Box<[Overaligned]>);
+	assert_eq!(create(&name,buffer).err().unwrap().kind(),io::ErrorKind::InvalidInput);
+	assert_eq!(attach::<u8>(&name).err().unwrap().kind(),io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_consistent_while_writing(){
+	let name = name("stress");
+	let mut w = create(&name,CircularBuffer::from(Box::new([[0u64;2];16]) as Box<[[u64;2]]>)).unwrap();
+	let r = attach::<[u64;2]>(&name).unwrap();
+
+	let writer = thread::spawn(move ||{
+		for i in 1..=20_000u64{
+			w.queue([i,!i]);
+			if i % 64 == 0{
+				thread::yield_now();
+			}
+		}
+	});
+
+	let mut out = [[0;2];16];
+	let mut last = 0;
+	while last < 20_000{
+		let seq = r.snapshot(&mut out);
+		assert!(seq >= last);
+		last = seq;
+		for (i,elem) in out.iter().enumerate(){
+			let expected = seq.saturating_sub(i as u64);
+			assert_eq!(*elem,if expected == 0{[0,0]}else{[expected,!expected]});
+		}
+		thread::yield_now();
+	}
+	writer.join().unwrap();
+}
@@ -0,0 +1,208 @@
+//!Raw binary snapshots of circular buffers of plain-old-data elements.
+//!
+//!A snapshot is a header of `HEADER_LEN` bytes followed by the bytes of the internal list:
+//!
+//!| Offset | Size | Field                                          |
+//!|--------|------|------------------------------------------------|
+//!| 0      | 4    | `MAGIC`                                        |
+//!| 4      | 4    | `ENDIANNESS_MARKER`                            |
+//!| 8      | 4    | `VERSION`                                      |
+//!| 12     | 4    | Element size in bytes                          |
+//!| 16     | 8    | Capacity in elements                           |
+//!| 24     | 8    | Internal index of `first`                      |
+//!
+//!The numbers and elements are written in the byte order of the writer, which the reader detects from the endianness marker.
+//!Snapshots written on a platform of the other byte order are converted using `ByteSwap`.
+
+#[cfg(test)]
+mod test;
+
+use core::mem;
+use core::ops::Deref;
+use std::error;
+use std::fmt;
+use std::io::{self,Read,Write};
+
+use bytemuck::{self,Pod};
+
+use CircularBuffer;
+use io_util::{read_u32_ne,read_u64_ne};
+
+pub const MAGIC: [u8;4] = *b"FCBS";
+pub const ENDIANNESS_MARKER: u32 = 0x0102_0304;
+pub const VERSION: u32 = 1;
+pub const HEADER_LEN: usize = 32;
+
+///Plain-old-data which can be converted between byte orders.
+pub trait ByteSwap: Pod{
+	///Reverses the byte order of each scalar in the value.
+	fn swap_bytes(&mut self);
+}
+
+macro_rules! impl_byte_swap_int{
+	($($t: ty),*) => {$(
+		impl ByteSwap for $t{
+			#[inline(always)]
+			fn swap_bytes(&mut self){*self = <$t>::swap_bytes(*self);}
+		}
+	)*};
+}
+impl_byte_swap_int!(u8,u16,u32,u64,u128,usize,i8,i16,i32,i64,i128,isize);
+
+impl ByteSwap for f32{
+	#[inline(always)]
+	fn swap_bytes(&mut self){*self = f32::from_bits(self.to_bits().swap_bytes());}
+}
+
+impl ByteSwap for f64{
+	#[inline(always)]
+	fn swap_bytes(&mut self){*self = f64::from_bits(self.to_bits().swap_bytes());}
+}
+
+impl<T: ByteSwap,const N: usize> ByteSwap for [T;N] where [T;N]: Pod{
+	#[inline]
+	fn swap_bytes(&mut self){
+		for elem in self.iter_mut(){
+			elem.swap_bytes();
+		}
+	}
+}
+
+///Error when reading or writing a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError{
+	Io(io::Error),
+	///The data does not start with `MAGIC`.
+	BadMagic,
+	///The endianness marker is neither in the native nor in the swapped byte order.
+	BadEndianness(u32),
+	UnsupportedVersion(u32),
+	///The element size of the snapshot does not match the requested type.
+	ElementSize{expected: usize,found: u64},
+	///The capacity is 0 or does not fit in memory.
+	BadCapacity(u64),
+	///The internal index of `first` is not less than the capacity.
+	FirstOutOfRange{first: u64,capacity: u64},
+}
+
+impl fmt::Display for SnapshotError{
+	fn fmt(&self,f: &mut fmt::Formatter) -> fmt::Result{
+		match *self{
+			SnapshotError::Io(ref e) => write!(f,"I/O error: {}",e),
+			SnapshotError::BadMagic => f.write_str("not a circular buffer snapshot"),
+			SnapshotError::BadEndianness(marker) => write!(f,"invalid endianness marker {:#010x}",marker),
+			SnapshotError::UnsupportedVersion(version) => write!(f,"unsupported snapshot version {}",version),
+			SnapshotError::ElementSize{expected,found} => write!(f,"element size is {} bytes instead of {}",found,expected),
+			SnapshotError::BadCapacity(capacity) => write!(f,"invalid capacity {}",capacity),
+			SnapshotError::FirstOutOfRange{first,capacity} => write!(f,"first index {} out of range for capacity {}",first,capacity),
+		}
+	}
+}
+
+impl error::Error for SnapshotError{
+	fn source(&self) -> Option<&(dyn error::Error + 'static)>{
+		match *self{
+			SnapshotError::Io(ref e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<io::Error> for SnapshotError{
+	#[inline]
+	fn from(e: io::Error) -> Self{SnapshotError::Io(e)}
+}
+
+impl<T,L> CircularBuffer<T,L> where
+	T: Pod,
+	L: Deref<Target=[T]>
+{
+	///Writes a snapshot of the buffer as it is stored.
+	pub fn write_to<W: Write>(&self,mut writer: W) -> Result<(),SnapshotError>{
+		writer.write_all(&header::<T>(self.list.len(),self.first))?;
+		writer.write_all(bytemuck::cast_slice(&self.list))?;
+		Ok(())
+	}
+
+	///Writes a snapshot of the buffer rotated so that `first` is 0, which is read back as an equal buffer.
+	pub fn write_normalized_to<W: Write>(&self,mut writer: W) -> Result<(),SnapshotError>{
+		writer.write_all(&header::<T>(self.list.len(),0))?;
+		writer.write_all(bytemuck::cast_slice(&self.list[self.first..]))?;
+		writer.write_all(bytemuck::cast_slice(&self.list[..self.first]))?;
+		Ok(())
+	}
+}
+
+impl<T: ByteSwap> CircularBuffer<T>{
+	///Reads a snapshot written by `write_to` or `write_normalized_to`, converting the byte order when needed.
+	pub fn read_from<R: Read>(mut reader: R) -> Result<Self,SnapshotError>{
+		let mut header = [0;HEADER_LEN];
+		reader.read_exact(&mut header)?;
+		if header[0..4] != MAGIC{
+			return Err(SnapshotError::BadMagic);
+		}
+		let swapped = match read_u32(&header[4..8],false){
+			ENDIANNESS_MARKER => false,
+			marker if marker.swap_bytes() == ENDIANNESS_MARKER => true,
+			marker => return Err(SnapshotError::BadEndianness(marker)),
+		};
+		let version = read_u32(&header[8..12],swapped);
+		if version != VERSION{
+			return Err(SnapshotError::UnsupportedVersion(version));
+		}
+		let size = read_u32(&header[12..16],swapped) as u64;
+		if size != mem::size_of::<T>() as u64{
+			return Err(SnapshotError::ElementSize{expected: mem::size_of::<T>(),found: size});
+		}
+		let capacity = read_u64(&header[16..24],swapped);
+		let first = read_u64(&header[24..32],swapped);
+		if capacity == 0 || capacity.checked_mul(size).is_none_or(|len| len > isize::MAX as u64){
+			return Err(SnapshotError::BadCapacity(capacity));
+		}
+		if first >= capacity{
+			return Err(SnapshotError::FirstOutOfRange{first,capacity});
+		}
+
+		//The capacity is untrusted, so nothing is allocated up front and the buffer only grows with the bytes actually read
+		let len = capacity * size;
+		let mut bytes = Vec::new();
+		reader.take(len).read_to_end(&mut bytes)?;
+		if (bytes.len() as u64) < len{
+			return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+		}
+		let mut list: Box<[T]> = if size == 0{
+			vec![T::zeroed();capacity as usize].into_boxed_slice()
+		}else{
+			bytes.chunks_exact(size as usize).map(bytemuck::pod_read_unaligned).collect()
+		};
+		if swapped{
+			for elem in list.iter_mut(){
+				elem.swap_bytes();
+			}
+		}
+		Ok(unsafe{CircularBuffer::from_raw_parts(list,first as usize)})
+	}
+}
+
+fn header<T>(capacity: usize,first: usize) -> [u8;HEADER_LEN]{
+	let mut header = [0;HEADER_LEN];
+	header[0..4].copy_from_slice(&MAGIC);
+	header[4..8].copy_from_slice(&ENDIANNESS_MARKER.to_ne_bytes());
+	header[8..12].copy_from_slice(&VERSION.to_ne_bytes());
+	header[12..16].copy_from_slice(&(mem::size_of::<T>() as u32).to_ne_bytes());
+	header[16..24].copy_from_slice(&(capacity as u64).to_ne_bytes());
+	header[24..32].copy_from_slice(&(first as u64).to_ne_bytes());
+	header
+}
+
+#[inline]
+fn read_u32(bytes: &[u8],swapped: bool) -> u32{
+	let n = read_u32_ne(bytes);
+	if swapped{n.swap_bytes()}else{n}
+}
+
+#[inline]
+fn read_u64(bytes: &[u8],swapped: bool) -> u64{
+	let n = read_u64_ne(bytes);
+	if swapped{n.swap_bytes()}else{n}
+}
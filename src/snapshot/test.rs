@@ -0,0 +1,95 @@
+use super::*;
+
+fn buffer() -> CircularBuffer<u32>{
+	let mut b = CircularBuffer::from(Box::new([0,0,0,0u32]) as Box<[u32]>);
+	for i in 1..=6{
+		b.queue(i);
+	}
+	b
+}
+
+///Converts a snapshot of elements with a single scalar of `size` bytes to the other byte order.
+fn swap_snapshot(bytes: &mut [u8],size: usize){
+	for field in [4..8,8..12,12..16,16..24,24..32]{
+		bytes[field].reverse();
+	}
+	for elem in bytes[HEADER_LEN..].chunks_mut(size){
+		elem.reverse();
+	}
+}
+
+#[test]
+fn test_roundtrip(){
+	let b = buffer();
+	let mut bytes = Vec::new();
+	b.write_to(&mut bytes).unwrap();
+	assert_eq!(bytes.len(),HEADER_LEN + 16);
+	let read = CircularBuffer::<u32>::read_from(&bytes[..]).unwrap();
+	assert_eq!(read,b);
+	assert_eq!(read.into_raw_parts().1,b.into_raw_parts().1);
+}
+
+#[test]
+fn test_normalized(){
+	let b = buffer();
+	let mut bytes = Vec::new();
+	b.write_normalized_to(&mut bytes).unwrap();
+	let (list,first) = CircularBuffer::<u32>::read_from(&bytes[..]).unwrap().into_raw_parts();
+	assert_eq!(first,0);
+	assert_eq!(*list,[6,5,4,3]);
+	assert!(CircularBuffer::from(list).iter().eq(b.iter()));
+}
+
+#[test]
+fn test_cross_endian(){
+	let b = buffer();
+	let mut bytes = Vec::new();
+	b.write_to(&mut bytes).unwrap();
+	swap_snapshot(&mut bytes,4);
+	assert_eq!(CircularBuffer::<u32>::read_from(&bytes[..]).unwrap(),b);
+
+	let f: CircularBuffer<[f64;2]> = CircularBuffer::from(Box::new([[1.5,-2.0],[0.25,1e300]]) as Box<[[f64;2]]>);
+	let mut bytes = Vec::new();
+	f.write_to(&mut bytes).unwrap();
+	swap_snapshot(&mut bytes,8);
+	assert_eq!(CircularBuffer::<[f64;2]>::read_from(&bytes[..]).unwrap(),f);
+}
+
+#[test]
+fn test_errors(){
+	let mut bytes = Vec::new();
+	buffer().write_to(&mut bytes).unwrap();
+
+	let read = |bytes: &[u8]| CircularBuffer::<u32>::read_from(bytes).unwrap_err();
+	assert!(matches!(CircularBuffer::<u16>::read_from(&bytes[..]).unwrap_err(),SnapshotError::ElementSize{expected: 2,found: 4}));
+	assert!(matches!(read(&bytes[..bytes.len() - 1]),SnapshotError::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof));
+	assert!(matches!(read(&bytes[..10]),SnapshotError::Io(_)));
+
+	let mut bad = bytes.clone();
+	bad[0] = b'X';
+	assert!(matches!(read(&bad),SnapshotError::BadMagic));
+
+	let mut bad = bytes.clone();
+	bad[4..8].copy_from_slice(&[1,2,2,1]);
+	assert!(matches!(read(&bad),SnapshotError::BadEndianness(_)));
+
+	let mut bad = bytes.clone();
+	bad[8..12].copy_from_slice(&2u32.to_ne_bytes());
+	assert!(matches!(read(&bad),SnapshotError::UnsupportedVersion(2)));
+
+	let mut bad = bytes.clone();
+	bad[16..24].copy_from_slice(&0u64.to_ne_bytes());
+	assert!(matches!(read(&bad),SnapshotError::BadCapacity(0)));
+
+	//Claims more elements than can be allocated, or than are present
+	let mut bad = bytes[..HEADER_LEN].to_vec();
+	bad[16..24].copy_from_slice(&(1u64 << 40).to_ne_bytes());
+	assert!(matches!(read(&bad),SnapshotError::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof));
+	bad[16..24].copy_from_slice(&(1u64 << 20).to_ne_bytes());
+	assert!(matches!(read(&bad),SnapshotError::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+	let mut bad = bytes.clone();
+	bad[24..32].copy_from_slice(&4u64.to_ne_bytes());
+	assert!(matches!(read(&bad),SnapshotError::FirstOutOfRange{first: 4,capacity: 4}));
+	assert_eq!(read(&bad).to_string(),"first index 4 out of range for capacity 4");
+}
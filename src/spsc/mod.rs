@@ -0,0 +1,239 @@
+//!Lock-free single-producer/single-consumer ring, split into a `Producer` and a `Consumer` half.
+//!
+//!The producer can either reject elements when the ring is full (`push`),
+//!or overwrite the oldest element (`push_overwrite`), matching the always filled semantics of `CircularBuffer`.
+
+#[cfg(test)]
+mod test;
+
+use core::mem::MaybeUninit;
+use core::ptr;
+
+use sync::{Arc,AtomicUsize,Ordering,UnsafeCell,spin_loop};
+
+///Value of `Shared::reading` when the consumer is not reading any slot.
+const IDLE: usize = usize::MAX;
+
+struct Shared<T>{
+	///One more slot than the capacity, so that the producer is able to overwrite the oldest element
+	///while the consumer is still moving out the element before it.
+	slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+	///Index of the oldest element. Only increases, and wraps around at `usize::MAX`.
+	///Both the producer (when overwriting) and the consumer advance it.
+	head: AtomicUsize,
+	///Index after the newest element. Only written by the producer.
+	tail: AtomicUsize,
+	///Slot which the consumer is currently moving an element out of, or `IDLE`.
+	reading: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Shared<T>{}
+unsafe impl<T: Send> Sync for Shared<T>{}
+
+impl<T> Shared<T>{
+	#[inline(always)]
+	fn capacity(&self) -> usize{self.slots.len() - 1}
+
+	#[inline(always)]
+	fn slot(&self,index: usize) -> usize{index % self.slots.len()}
+
+	#[inline]
+	fn len(&self) -> usize{
+		let head = self.head.load(Ordering::SeqCst);
+		let tail = self.tail.load(Ordering::Acquire);
+		tail.wrapping_sub(head).min(self.capacity())
+	}
+
+	///# Safety
+	///
+	///The slot must be initialized and exclusively owned by the caller.
+	#[inline]
+	unsafe fn read(&self,index: usize) -> T{
+		self.slots[self.slot(index)].with(|p| ptr::read(p as *const T))
+	}
+
+	///# Safety
+	///
+	///The slot must be uninitialized and exclusively owned by the caller.
+	#[inline]
+	unsafe fn write(&self,index: usize,elem: T){
+		self.slots[self.slot(index)].with_mut(|p| ptr::write(p as *mut T,elem))
+	}
+}
+
+impl<T> Drop for Shared<T>{
+	fn drop(&mut self){
+		let mut head = self.head.load(Ordering::SeqCst);
+		let tail = self.tail.load(Ordering::SeqCst);
+		while head != tail{
+			drop(unsafe{self.read(head)});
+			head = head.wrapping_add(1);
+		}
+	}
+}
+
+///Creates a ring which is able to hold `capacity` elements, returning its two halves.
+///
+///# Panics
+///
+///When `capacity` is 0.
+pub fn ring<T>(capacity: usize) -> (Producer<T>,Consumer<T>){
+	assert!(capacity > 0);
+
+	let shared = Arc::new(Shared{
+		slots: (0..capacity + 1).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect::<Vec<_>>().into_boxed_slice(),
+		head: AtomicUsize::new(0),
+		tail: AtomicUsize::new(0),
+		reading: AtomicUsize::new(IDLE),
+	});
+	(Producer{shared: shared.clone(),tail: 0},Consumer{shared})
+}
+
+///The writing half of a ring.
+pub struct Producer<T>{
+	shared: Arc<Shared<T>>,
+	///Local copy of `Shared::tail`.
+	tail: usize,
+}
+
+impl<T> Producer<T>{
+	///Returns the maximum number of elements in the ring.
+	#[inline]
+	pub fn capacity(&self) -> usize{self.shared.capacity()}
+
+	///Returns the number of elements in the ring.
+	///The consumer may concurrently decrease it.
+	#[inline]
+	pub fn len(&self) -> usize{self.shared.len()}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool{self.len() == 0}
+
+	#[inline]
+	pub fn is_full(&self) -> bool{self.len() == self.capacity()}
+
+	///Returns the number of elements which can be written to the ring starting at the local tail without evicting anything.
+	fn free(&self) -> usize{
+		let shared = &*self.shared;
+		let head = shared.head.load(Ordering::SeqCst);
+		let free = shared.capacity() - self.tail.wrapping_sub(head);
+
+		//The consumer may still be moving out an element which was evicted from under it
+		match shared.reading.load(Ordering::SeqCst){
+			IDLE => free,
+			reading => free.min((reading + shared.slots.len() - shared.slot(self.tail)) % shared.slots.len()),
+		}
+	}
+
+	///Enqueues the given element, or returns it when the ring is full.
+	pub fn push(&mut self,elem: T) -> Result<(),T>{
+		if self.free() == 0{
+			return Err(elem);
+		}
+		unsafe{self.shared.write(self.tail,elem)};
+		self.tail = self.tail.wrapping_add(1);
+		self.shared.tail.store(self.tail,Ordering::Release);
+		Ok(())
+	}
+
+	///Enqueues as many elements from the slice as there is room for, returning how many were enqueued.
+	pub fn push_slice(&mut self,elems: &[T]) -> usize where
+		T: Clone
+	{
+		let count = self.free().min(elems.len());
+		for (i,elem) in elems[..count].iter().enumerate(){
+			unsafe{self.shared.write(self.tail.wrapping_add(i),elem.clone())};
+		}
+		self.tail = self.tail.wrapping_add(count);
+		self.shared.tail.store(self.tail,Ordering::Release);
+		count
+	}
+
+	///Enqueues the given element, dequeuing and returning the oldest element when the ring is full.
+	///
+	///This never fails, but may spin for the short moment when the consumer is moving out
+	///the element in the slot which is about to be written to.
+	pub fn push_overwrite(&mut self,elem: T) -> Option<T>{
+		let shared = &*self.shared;
+		let mut evicted = None;
+		loop{
+			let head = shared.head.load(Ordering::SeqCst);
+			if self.tail.wrapping_sub(head) < shared.capacity(){
+				break;
+			}
+			if shared.head.compare_exchange(head,head.wrapping_add(1),Ordering::SeqCst,Ordering::SeqCst).is_ok(){
+				evicted = Some(unsafe{shared.read(head)});
+				break;
+			}
+		}
+
+		let slot = shared.slot(self.tail);
+		while shared.reading.load(Ordering::SeqCst) == slot{
+			spin_loop();
+		}
+
+		unsafe{shared.write(self.tail,elem)};
+		self.tail = self.tail.wrapping_add(1);
+		shared.tail.store(self.tail,Ordering::Release);
+		evicted
+	}
+
+	///Enqueues all elements from the slice, dequeuing the oldest elements when the ring is full.
+	///Returns the number of elements which were dequeued and dropped.
+	pub fn push_slice_overwrite(&mut self,elems: &[T]) -> usize where
+		T: Clone
+	{
+		elems.iter().filter(|elem| self.push_overwrite((*elem).clone()).is_some()).count()
+	}
+}
+
+///The reading half of a ring.
+pub struct Consumer<T>{
+	shared: Arc<Shared<T>>,
+}
+
+impl<T> Consumer<T>{
+	///Returns the maximum number of elements in the ring.
+	#[inline]
+	pub fn capacity(&self) -> usize{self.shared.capacity()}
+
+	///Returns the number of elements in the ring.
+	///The producer may concurrently increase it.
+	#[inline]
+	pub fn len(&self) -> usize{self.shared.len()}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool{self.len() == 0}
+
+	///Dequeues the oldest element, or returns `None` when the ring is empty.
+	pub fn pop(&mut self) -> Option<T>{
+		let shared = &*self.shared;
+		loop{
+			let head = shared.head.load(Ordering::SeqCst);
+			if head == shared.tail.load(Ordering::Acquire){
+				return None;
+			}
+
+			//Announce the read before claiming the element so that the producer does not overwrite it
+			shared.reading.store(shared.slot(head),Ordering::SeqCst);
+			if shared.head.compare_exchange(head,head.wrapping_add(1),Ordering::SeqCst,Ordering::SeqCst).is_ok(){
+				let elem = unsafe{shared.read(head)};
+				shared.reading.store(IDLE,Ordering::SeqCst);
+				return Some(elem);
+			}
+			shared.reading.store(IDLE,Ordering::SeqCst);
+		}
+	}
+
+	///Dequeues elements into the slice from the oldest, until either the slice is filled or the ring is empty.
+	///Returns the number of dequeued elements.
+	pub fn pop_slice(&mut self,out: &mut [T]) -> usize{
+		for (i,slot) in out.iter_mut().enumerate(){
+			match self.pop(){
+				Some(elem) => *slot = elem,
+				None => return i,
+			}
+		}
+		out.len()
+	}
+}
@@ -0,0 +1,197 @@
+use super::*;
+
+#[cfg(not(loom))]
+mod single{
+	use super::*;
+	use std::rc::Rc;
+
+	#[test]
+	fn test_push_pop(){
+		let (mut p,mut c) = ring(3);
+		assert_eq!(p.capacity(),3);
+		assert!(c.is_empty());
+		assert_eq!(c.pop(),None);
+
+		assert_eq!(p.push('a'),Ok(()));
+		assert_eq!(p.push('b'),Ok(()));
+		assert_eq!(p.push('c'),Ok(()));
+		assert!(p.is_full());
+		assert_eq!(p.push('d'),Err('d'));
+
+		assert_eq!(c.pop(),Some('a'));
+		assert_eq!(p.push('d'),Ok(()));
+		assert_eq!(c.len(),3);
+		assert_eq!(c.pop(),Some('b'));
+		assert_eq!(c.pop(),Some('c'));
+		assert_eq!(c.pop(),Some('d'));
+		assert_eq!(c.pop(),None);
+	}
+
+	#[test]
+	fn test_push_overwrite(){
+		let (mut p,mut c) = ring(3);
+		assert_eq!(p.push_overwrite('a'),None);
+		assert_eq!(p.push_overwrite('b'),None);
+		assert_eq!(p.push_overwrite('c'),None);
+		assert_eq!(p.push_overwrite('d'),Some('a'));
+		assert_eq!(p.push_overwrite('e'),Some('b'));
+
+		assert_eq!(c.pop(),Some('c'));
+		assert_eq!(p.push_overwrite('f'),None);
+		assert_eq!(p.push_overwrite('g'),Some('d'));
+		assert_eq!(c.pop(),Some('e'));
+		assert_eq!(c.pop(),Some('f'));
+		assert_eq!(c.pop(),Some('g'));
+		assert_eq!(c.pop(),None);
+	}
+
+	#[test]
+	fn test_slices(){
+		let (mut p,mut c) = ring(4);
+		assert_eq!(p.push_slice(&[1,2,3]),3);
+		assert_eq!(p.push_slice(&[4,5,6]),1);
+
+		let mut out = [0;3];
+		assert_eq!(c.pop_slice(&mut out),3);
+		assert_eq!(out,[1,2,3]);
+
+		assert_eq!(p.push_slice_overwrite(&[5,6,7,8,9]),2);
+		let mut out = [0;6];
+		assert_eq!(c.pop_slice(&mut out),4);
+		assert_eq!(&out[..4],&[6,7,8,9]);
+	}
+
+	#[test]
+	fn test_drop(){
+		let x = Rc::new(());
+		{
+			let (mut p,mut c) = ring(2);
+			p.push(x.clone()).unwrap();
+			p.push(x.clone()).unwrap();
+			p.push_overwrite(x.clone());
+			c.pop();
+			assert_eq!(Rc::strong_count(&x),2);
+		}
+		assert_eq!(Rc::strong_count(&x),1);
+	}
+
+	#[test]
+	fn test_stress_reject(){
+		const COUNT: usize = 200_000;
+		let (mut p,mut c) = ring(7);
+		let producer = ::std::thread::spawn(move ||{
+			let mut i = 0;
+			while i < COUNT{
+				let pushed = if i % 3 == 0{
+					let elems: Vec<usize> = (i..(i + 5).min(COUNT)).collect();
+					p.push_slice(&elems)
+				}else{
+					p.push(i).map(|_| 1).unwrap_or(0)
+				};
+				if pushed == 0{
+					::std::thread::yield_now();
+				}
+				i += pushed;
+			}
+		});
+
+		let mut expected = 0;
+		let mut out = [0;4];
+		while expected < COUNT{
+			let n = c.pop_slice(&mut out);
+			if n == 0{
+				::std::thread::yield_now();
+			}
+			for &x in &out[..n]{
+				assert_eq!(x,expected);
+				expected += 1;
+			}
+		}
+		producer.join().unwrap();
+		assert_eq!(c.pop(),None);
+	}
+
+	#[test]
+	fn test_stress_overwrite(){
+		const COUNT: usize = 200_000;
+		let (mut p,mut c) = ring(5);
+		let producer = ::std::thread::spawn(move ||{
+			let mut evicted = Vec::new();
+			for i in 0..COUNT{
+				evicted.extend(p.push_overwrite(Box::new(i)));
+			}
+			evicted
+		});
+
+		let mut popped: Vec<Box<usize>> = Vec::new();
+		while popped.last().is_none_or(|x| **x != COUNT - 1){
+			match c.pop(){
+				Some(x) => popped.push(x),
+				None => ::std::thread::yield_now(),
+			}
+		}
+		let evicted = producer.join().unwrap();
+
+		for w in popped.windows(2){
+			assert!(w[0] < w[1]);
+		}
+		let mut all: Vec<usize> = popped.into_iter().chain(evicted).map(|x| *x).collect();
+		all.sort();
+		assert_eq!(all,(0..COUNT).collect::<Vec<_>>());
+	}
+}
+
+#[cfg(loom)]
+mod model{
+	use super::*;
+	use loom::thread;
+
+	#[test]
+	fn test_loom_reject(){
+		loom::model(||{
+			let (mut p,mut c) = ring(2);
+			let producer = thread::spawn(move ||{
+				for i in 0..3{
+					while p.push(i).is_err(){
+						thread::yield_now();
+					}
+				}
+			});
+			let mut expected = 0;
+			while expected < 3{
+				match c.pop(){
+					Some(x) => {assert_eq!(x,expected); expected += 1;},
+					None => thread::yield_now(),
+				}
+			}
+			producer.join().unwrap();
+		});
+	}
+
+	#[test]
+	fn test_loom_overwrite(){
+		loom::model(||{
+			let (mut p,mut c) = ring(1);
+			let producer = thread::spawn(move ||{
+				let mut evicted = Vec::new();
+				for i in 0..3{
+					evicted.extend(p.push_overwrite(i));
+				}
+				evicted
+			});
+			let mut popped = Vec::new();
+			for _ in 0..2{
+				popped.extend(c.pop());
+			}
+			let evicted = producer.join().unwrap();
+			popped.extend(c.pop());
+
+			for w in popped.windows(2){
+				assert!(w[0] < w[1]);
+			}
+			let mut all: Vec<i32> = popped.into_iter().chain(evicted).collect();
+			all.sort();
+			assert_eq!(all,vec![0,1,2]);
+		});
+	}
+}
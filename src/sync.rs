@@ -0,0 +1,40 @@
+//!Synchronization primitives, replaced by the ones from `loom` when model checking.
+
+#[cfg(all(test,loom))]
+pub(crate) use loom::sync::Arc;
+#[cfg(all(test,loom))]
+pub(crate) use loom::sync::atomic::{AtomicUsize,Ordering};
+#[cfg(all(test,loom))]
+pub(crate) use loom::hint::spin_loop;
+#[cfg(all(test,loom))]
+pub(crate) use loom::cell::UnsafeCell;
+
+#[cfg(not(all(test,loom)))]
+pub(crate) use std::sync::Arc;
+#[cfg(not(all(test,loom)))]
+pub(crate) use core::sync::atomic::{AtomicUsize,Ordering};
+#[cfg(not(all(test,loom)))]
+pub(crate) use core::hint::spin_loop;
+
+///`core::cell::UnsafeCell` with the closure based interface of `loom::cell::UnsafeCell`.
+#[cfg(not(all(test,loom)))]
+#[derive(Debug)]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(all(test,loom)))]
+impl<T> UnsafeCell<T>{
+	#[inline(always)]
+	pub(crate) fn new(data: T) -> Self{
+		UnsafeCell(core::cell::UnsafeCell::new(data))
+	}
+
+	#[inline(always)]
+	pub(crate) fn with<R,F: FnOnce(*const T) -> R>(&self,f: F) -> R{
+		f(self.0.get())
+	}
+
+	#[inline(always)]
+	pub(crate) fn with_mut<R,F: FnOnce(*mut T) -> R>(&self,f: F) -> R{
+		f(self.0.get())
+	}
+}
@@ -0,0 +1,35 @@
+//!Fixtures shared by the tests of several modules.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use aggregate::{FnMonoid,Monoid};
+
+pub struct Sum;
+impl Monoid for Sum{
+	type Value = u32;
+	fn identity(&self) -> u32{0}
+	fn combine(&self,a: &u32,b: &u32) -> u32{a + b}
+}
+
+///Non-commutative monoid of string concatenation.
+pub type Concat = FnMonoid<String,fn(&String,&String) -> String>;
+
+pub fn concat() -> Concat{
+	fn combine(a: &String,b: &String) -> String{format!("{}{}",a,b)}
+	FnMonoid{identity: String::new(),combine}
+}
+
+///Returns a path in the temporary directory which is unique to the process and `name`.
+pub fn temp_path(name: &str) -> PathBuf{
+	env::temp_dir().join(format!("fixed_circular_buffer-{}-{}",process::id(),name))
+}
+
+///Returns a path like `temp_path`, removing a directory left over at it.
+pub fn temp_dir(name: &str) -> PathBuf{
+	let dir = temp_path(name);
+	let _ = fs::remove_dir_all(&dir);
+	dir
+}
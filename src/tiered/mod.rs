@@ -0,0 +1,396 @@
+//!Buffer keeping the most recent elements in memory and passing the evicted ones on to a secondary sink, such as files on disk.
+
+#[cfg(test)]
+mod test;
+
+use core::convert::{Infallible,TryFrom};
+use core::fmt;
+use core::marker::PhantomData;
+use std::error;
+use std::fs::{self,File,OpenOptions};
+use std::io::{self,BufReader,BufWriter,Read,Write};
+use std::path::{Path,PathBuf};
+
+use CircularBuffer;
+
+///Destination of the elements evicted from a `Tiered` buffer.
+pub trait EvictionSink<T>{
+	type Error;
+
+	///Takes an evicted element, which are passed from the oldest to the most recent.
+	///On failure, the element is returned with the error.
+	fn evict(&mut self,elem: T) -> Result<(),SpillError<Self::Error,T>>;
+
+	///Makes sure that the evicted elements are persisted.
+	fn flush(&mut self) -> Result<(),Self::Error>{Ok(())}
+}
+
+impl<T> EvictionSink<T> for Vec<T>{
+	type Error = Infallible;
+
+	#[inline]
+	fn evict(&mut self,elem: T) -> Result<(),SpillError<Infallible,T>>{
+		self.push(elem);
+		Ok(())
+	}
+}
+
+///Error of an `EvictionSink`, with the element which it did not take.
+#[derive(Clone,Debug,Eq,PartialEq,Hash)]
+pub struct SpillError<E,T>{
+	pub error: E,
+	pub elem: T,
+}
+
+impl<E,T> SpillError<E,T>{
+	#[inline(always)]
+	pub fn new(error: E,elem: T) -> Self{
+		SpillError{error,elem}
+	}
+}
+
+impl<E: fmt::Display,T> fmt::Display for SpillError<E,T>{
+	fn fmt(&self,f: &mut fmt::Formatter) -> fmt::Result{
+		write!(f,"failed to spill an evicted element: {}",self.error)
+	}
+}
+
+impl<E: error::Error + 'static,T: fmt::Debug> error::Error for SpillError<E,T>{
+	fn source(&self) -> Option<&(dyn error::Error + 'static)>{
+		Some(&self.error)
+	}
+}
+
+///Buffer of the most recent `capacity` elements, which passes every evicted element on to an `EvictionSink`.
+///
+///Unlike `CircularBuffer`, it starts out empty, so only queued elements are evicted.
+#[derive(Clone,Debug)]
+pub struct Tiered<T,S>{
+	buffer: CircularBuffer<Option<T>>,
+	len: usize,
+	sink: S,
+}
+
+impl<T,S: EvictionSink<T>> Tiered<T,S>{
+	///Constructs an empty buffer holding up to `capacity` elements in memory.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	pub fn new(capacity: usize,sink: S) -> Self{
+		assert!(capacity > 0);
+		Tiered{
+			buffer: CircularBuffer::from((0..capacity).map(|_| None).collect::<Vec<_>>()),
+			len: 0,
+			sink,
+		}
+	}
+
+	///Returns the maximum number of elements in memory.
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.buffer.len()}
+
+	///Returns the number of elements in memory.
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.len}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.len == 0}
+
+	#[inline(always)]
+	pub fn sink(&self) -> &S{&self.sink}
+
+	#[inline(always)]
+	pub fn sink_mut(&mut self) -> &mut S{&mut self.sink}
+
+	///Deconstructs the structure into the elements in memory (from the oldest to the most recent) and the sink.
+	pub fn into_inner(self) -> (Vec<T>,S){
+		let (list,first) = self.buffer.into_raw_parts();
+		let mut list = list.into_vec();
+		list.rotate_left(first);
+		let mut elems: Vec<T> = list.into_iter().flatten().collect();
+		elems.reverse();
+		(elems,self.sink)
+	}
+
+	///Returns the element in memory at `index`, where 0 is the most recent element.
+	#[inline]
+	pub fn get(&self,index: usize) -> Option<&T>{
+		if index < self.len{self.buffer.get(index).as_ref()}else{None}
+	}
+
+	///Returns an iterator over the elements in memory, from the oldest to the most recent.
+	#[inline]
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item=&T> + ExactSizeIterator{
+		(0..self.len).rev().map(move |index| self.buffer.get(index).as_ref().unwrap())
+	}
+
+	///Enqueues the given element, passing the oldest element on to the sink when the memory is full.
+	///When the sink fails, the evicted element is returned with the error.
+	pub fn queue(&mut self,elem: T) -> Result<(),SpillError<S::Error,T>>{
+		match self.buffer.queue(Some(elem)){
+			Some(evicted) => self.sink.evict(evicted),
+			None => {
+				self.len += 1;
+				Ok(())
+			},
+		}
+	}
+
+	///Passes all elements in memory on to the sink, and flushes it.
+	///When the sink fails, the element which it did not take and the more recent ones stay in memory.
+	pub fn spill_all(&mut self) -> Result<(),S::Error>{
+		while self.len > 0{
+			let index = self.len - 1;
+			let elem = self.buffer.get_mut(index).take().unwrap();
+			if let Err(e) = self.sink.evict(elem){
+				*self.buffer.get_mut(index) = Some(e.elem);
+				return Err(e.error);
+			}
+			self.len -= 1;
+		}
+		self.sink.flush()
+	}
+
+	#[inline]
+	pub fn flush(&mut self) -> Result<(),S::Error>{
+		self.sink.flush()
+	}
+}
+
+impl<T: Clone,E: Encoding<T> + Clone> Tiered<T,FileSink<T,E>>{
+	///Returns an iterator over all elements, first those on disk and then those in memory, from the oldest to the most recent.
+	///Flushes the sink first.
+	pub fn iter_all(&mut self) -> io::Result<impl Iterator<Item=io::Result<T>> + '_>{
+		self.sink.flush()?;
+		let disk = self.sink.reader()?;
+		let memory = (0..self.len).rev().map(move |index| Ok(self.buffer.get(index).clone().unwrap()));
+		Ok(disk.chain(memory))
+	}
+}
+
+///Conversion of elements to and from bytes for `FileSink`.
+pub trait Encoding<T>{
+	fn encode(&self,elem: &T,out: &mut Vec<u8>) -> io::Result<()>;
+	fn decode(&self,bytes: &[u8]) -> io::Result<T>;
+}
+
+///Encoding of plain-old-data as their in-memory bytes.
+#[cfg(feature = "pod")]
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct PodEncoding;
+
+#[cfg(feature = "pod")]
+impl<T: ::bytemuck::Pod> Encoding<T> for PodEncoding{
+	#[inline]
+	fn encode(&self,elem: &T,out: &mut Vec<u8>) -> io::Result<()>{
+		out.extend_from_slice(::bytemuck::bytes_of(elem));
+		Ok(())
+	}
+
+	fn decode(&self,bytes: &[u8]) -> io::Result<T>{
+		::bytemuck::try_pod_read_unaligned(bytes).map_err(|_| io::Error::new(io::ErrorKind::InvalidData,"element size mismatch"))
+	}
+}
+
+///Encoding of serializable elements with `bincode`.
+#[cfg(feature = "serde")]
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct BincodeEncoding;
+
+#[cfg(feature = "serde")]
+impl<T: ::serde::Serialize + ::serde::de::DeserializeOwned> Encoding<T> for BincodeEncoding{
+	#[inline]
+	fn encode(&self,elem: &T,out: &mut Vec<u8>) -> io::Result<()>{
+		::bincode::serialize_into(out,elem).map_err(|e| io::Error::new(io::ErrorKind::InvalidData,e))
+	}
+
+	#[inline]
+	fn decode(&self,bytes: &[u8]) -> io::Result<T>{
+		::bincode::deserialize(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData,e))
+	}
+}
+
+///Sink appending the evicted elements to segment files in a directory, starting a new segment when the current one reaches a size.
+///
+///Segments are named by their zero-padded number and the extension `spill`, and contain records of a little-endian 32 bit length followed by the encoded element.
+#[derive(Debug)]
+pub struct FileSink<T,E>{
+	dir: PathBuf,
+	encoding: E,
+	max_segment_len: u64,
+	///Number and writer of the current segment.
+	segment: Option<(u64,BufWriter<File>)>,
+	segment_len: u64,
+	scratch: Vec<u8>,
+	t: PhantomData<fn(T)>,
+}
+
+impl<T,E: Encoding<T>> FileSink<T,E>{
+	///Opens the directory (creating it when needed) for appending new segments after the existing ones.
+	///A segment is started when the current one has reached `max_segment_len` bytes.
+	pub fn new<P: Into<PathBuf>>(dir: P,max_segment_len: u64,encoding: E) -> io::Result<Self>{
+		let dir = dir.into();
+		fs::create_dir_all(&dir)?;
+		Ok(FileSink{
+			dir,
+			encoding,
+			max_segment_len,
+			segment: None,
+			segment_len: 0,
+			scratch: Vec::new(),
+			t: PhantomData,
+		})
+	}
+
+	#[inline(always)]
+	pub fn dir(&self) -> &Path{&self.dir}
+
+	///Returns the paths of the segments, from the oldest.
+	pub fn segments(&self) -> io::Result<Vec<PathBuf>>{
+		Ok(segments(&self.dir)?.into_iter().map(|(_,path)| path).collect())
+	}
+
+	///Returns an iterator over the elements in the segments, from the oldest.
+	///Elements which are not flushed yet are not included.
+	pub fn reader(&self) -> io::Result<SegmentReader<T,E>> where E: Clone{
+		SegmentReader::new(&self.dir,self.encoding.clone())
+	}
+
+	fn write(&mut self,elem: &T) -> io::Result<()>{
+		if self.segment.is_none() || self.segment_len >= self.max_segment_len{
+			self.rotate()?;
+		}
+		self.scratch.clear();
+		self.encoding.encode(elem,&mut self.scratch)?;
+		let len = u32::try_from(self.scratch.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput,"encoded element too large"))?;
+
+		let writer = &mut self.segment.as_mut().unwrap().1;
+		writer.write_all(&len.to_le_bytes())?;
+		writer.write_all(&self.scratch)?;
+		self.segment_len += 4 + len as u64;
+		Ok(())
+	}
+
+	fn rotate(&mut self) -> io::Result<()>{
+		if let Some((_,mut writer)) = self.segment.take(){
+			writer.flush()?;
+		}
+		let number = segments(&self.dir)?.last().map_or(0,|&(number,_)| number + 1);
+		let file = OpenOptions::new().write(true).create_new(true).open(self.dir.join(segment_name(number)))?;
+		self.segment = Some((number,BufWriter::new(file)));
+		self.segment_len = 0;
+		Ok(())
+	}
+}
+
+impl<T,E: Encoding<T>> EvictionSink<T> for FileSink<T,E>{
+	type Error = io::Error;
+
+	fn evict(&mut self,elem: T) -> Result<(),SpillError<io::Error,T>>{
+		match self.write(&elem){
+			Ok(()) => Ok(()),
+			Err(e) => Err(SpillError::new(e,elem)),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()>{
+		match self.segment{
+			Some((_,ref mut writer)) => writer.flush(),
+			None => Ok(()),
+		}
+	}
+}
+
+///Iterator over the elements in the segments of a `FileSink` directory, from the oldest.
+///A truncated or undecodable record is returned as an error, after which the iteration stops.
+#[derive(Debug)]
+pub struct SegmentReader<T,E>{
+	segments: ::std::vec::IntoIter<PathBuf>,
+	current: Option<BufReader<File>>,
+	encoding: E,
+	failed: bool,
+	t: PhantomData<fn() -> T>,
+}
+
+impl<T,E: Encoding<T>> SegmentReader<T,E>{
+	pub fn new<P: AsRef<Path>>(dir: P,encoding: E) -> io::Result<Self>{
+		let segments: Vec<PathBuf> = segments(dir.as_ref())?.into_iter().map(|(_,path)| path).collect();
+		Ok(SegmentReader{
+			segments: segments.into_iter(),
+			current: None,
+			encoding,
+			failed: false,
+			t: PhantomData,
+		})
+	}
+
+	///Reads the next record of the current segment, or `None` at its end.
+	fn read_record(&mut self) -> io::Result<Option<T>>{
+		let reader = self.current.as_mut().unwrap();
+		let mut len = [0;4];
+		let mut read = 0;
+		while read < len.len(){
+			match reader.read(&mut len[read..])?{
+				0 if read == 0 => return Ok(None),
+				0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+				n => read += n,
+			}
+		}
+		//The length is untrusted, so the buffer only grows with the bytes actually read
+		let len = u32::from_le_bytes(len) as u64;
+		let mut bytes = Vec::new();
+		reader.take(len).read_to_end(&mut bytes)?;
+		if (bytes.len() as u64) < len{
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		}
+		self.encoding.decode(&bytes).map(Some)
+	}
+}
+
+impl<T,E: Encoding<T>> Iterator for SegmentReader<T,E>{
+	type Item = io::Result<T>;
+
+	fn next(&mut self) -> Option<Self::Item>{
+		while !self.failed{
+			if self.current.is_none(){
+				let path = self.segments.next()?;
+				match File::open(path){
+					Ok(file) => self.current = Some(BufReader::new(file)),
+					Err(e) => {
+						self.failed = true;
+						return Some(Err(e));
+					},
+				}
+			}
+			match self.read_record(){
+				Ok(Some(elem)) => return Some(Ok(elem)),
+				Ok(None) => self.current = None,
+				Err(e) => {
+					self.failed = true;
+					return Some(Err(e));
+				},
+			}
+		}
+		None
+	}
+}
+
+fn segment_name(number: u64) -> String{
+	format!("{:016}.spill",number)
+}
+
+///Returns the numbers and paths of the segments in the directory, ordered by number.
+fn segments(dir: &Path) -> io::Result<Vec<(u64,PathBuf)>>{
+	let mut out = Vec::new();
+	for entry in fs::read_dir(dir)?{
+		let path = entry?.path();
+		if path.extension().is_some_and(|extension| extension == "spill"){
+			if let Some(number) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok()){
+				out.push((number,path));
+			}
+		}
+	}
+	out.sort();
+	Ok(out)
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+use test_util::temp_dir;
+
+///Encoding of `u32` as decimal text, to not depend on optional features.
+#[derive(Copy,Clone,Debug)]
+struct Decimal;
+
+impl Encoding<u32> for Decimal{
+	fn encode(&self,elem: &u32,out: &mut Vec<u8>) -> io::Result<()>{
+		out.extend_from_slice(elem.to_string().as_bytes());
+		Ok(())
+	}
+
+	fn decode(&self,bytes: &[u8]) -> io::Result<u32>{
+		String::from_utf8_lossy(bytes).parse().map_err(|_| io::Error::from(io::ErrorKind::InvalidData))
+	}
+}
+
+///Sink taking only the first `remaining` elements.
+#[derive(Debug)]
+struct Limited{
+	taken: Vec<u32>,
+	remaining: usize,
+}
+
+impl EvictionSink<u32> for Limited{
+	type Error = ();
+
+	fn evict(&mut self,elem: u32) -> Result<(),SpillError<(),u32>>{
+		if self.remaining == 0{
+			return Err(SpillError::new((),elem));
+		}
+		self.remaining -= 1;
+		self.taken.push(elem);
+		Ok(())
+	}
+}
+
+#[test]
+fn test_vec_sink(){
+	let mut t = Tiered::new(3,Vec::new());
+	assert!(t.is_empty());
+	for i in 0..5{
+		t.queue(i).unwrap();
+	}
+	assert_eq!(t.len(),3);
+	assert_eq!(*t.sink(),[0,1]);
+	assert!(t.iter().eq(&[2,3,4]));
+	assert!(t.iter().rev().eq(&[4,3,2]));
+	assert_eq!(t.get(0),Some(&4));
+	assert_eq!(t.get(3),None);
+
+	let (memory,sink) = t.clone().into_inner();
+	assert_eq!(memory,[2,3,4]);
+	assert_eq!(sink,[0,1]);
+
+	t.spill_all().unwrap();
+	assert!(t.is_empty());
+	assert_eq!(*t.sink(),[0,1,2,3,4]);
+	t.queue(5).unwrap();
+	assert_eq!(t.into_inner(),(vec![5],vec![0,1,2,3,4]));
+}
+
+#[test]
+fn test_failing_sink(){
+	let mut t = Tiered::new(2,Limited{taken: Vec::new(),remaining: 1});
+	for i in 0..3{
+		t.queue(i).unwrap();
+	}
+	assert_eq!(t.queue(3),Err(SpillError::new((),1)));
+	assert!(t.iter().eq(&[2,3]));
+
+	t.sink_mut().remaining = 1;
+	assert_eq!(t.spill_all(),Err(()));
+	assert_eq!(t.sink().taken,[0,2]);
+	assert!(t.iter().eq(&[3]));
+	t.sink_mut().remaining = 1;
+	t.spill_all().unwrap();
+	assert!(t.is_empty());
+	assert_eq!(t.sink().taken,[0,2,3]);
+}
+
+#[test]
+fn test_file_sink_rotation(){
+	let dir = temp_dir("tiered-rotation");
+	let mut t = Tiered::new(4,FileSink::new(&dir,12,Decimal).unwrap());
+	for i in 0..20{
+		t.queue(i).unwrap();
+	}
+	let all: Vec<u32> = t.iter_all().unwrap().map(Result::unwrap).collect();
+	assert_eq!(all,(0..20).collect::<Vec<_>>());
+
+	//Each record of one digit takes 5 bytes, so every segment holds 3 records
+	let segments = t.sink().segments().unwrap();
+	assert_eq!(segments.len(),16usize.div_ceil(3));
+	assert!(segments[0].ends_with("0000000000000000.spill"));
+
+	//A new sink continues after the existing segments
+	drop(t);
+	let mut t = Tiered::new(2,FileSink::new(&dir,1000,Decimal).unwrap());
+	for i in 20..25{
+		t.queue(i).unwrap();
+	}
+	let all: Vec<u32> = t.iter_all().unwrap().map(Result::unwrap).collect();
+	assert_eq!(all,(0..16).chain(20..25).collect::<Vec<_>>());
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_truncated_segment(){
+	let dir = temp_dir("tiered-truncated");
+	let mut sink = FileSink::new(&dir,1000,Decimal).unwrap();
+	for i in 100..103{
+		sink.evict(i).unwrap();
+	}
+	sink.flush().unwrap();
+	let path = sink.segments().unwrap().remove(0);
+	let bytes = fs::read(&path).unwrap();
+	fs::write(&path,&bytes[..bytes.len() - 1]).unwrap();
+
+	let mut reader = SegmentReader::new(&dir,Decimal).unwrap();
+	assert_eq!(reader.next().unwrap().unwrap(),100);
+	assert_eq!(reader.next().unwrap().unwrap(),101);
+	assert_eq!(reader.next().unwrap().unwrap_err().kind(),io::ErrorKind::UnexpectedEof);
+	assert!(reader.next().is_none());
+
+	//A corrupted length larger than the rest of the segment
+	let mut bytes = bytes;
+	bytes[0..4].copy_from_slice(&u32::MAX.to_le_bytes());
+	fs::write(&path,&bytes).unwrap();
+	let mut reader = SegmentReader::new(&dir,Decimal).unwrap();
+	assert_eq!(reader.next().unwrap().unwrap_err().kind(),io::ErrorKind::UnexpectedEof);
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "pod")]
+#[test]
+fn test_pod_encoding(){
+	let dir = temp_dir("tiered-pod");
+	let mut t = Tiered::new(2,FileSink::new(&dir,64,PodEncoding).unwrap());
+	for i in 0..10u64{
+		t.queue([i,i * i]).unwrap();
+	}
+	let all: Vec<[u64;2]> = t.iter_all().unwrap().map(Result::unwrap).collect();
+	assert_eq!(all,(0..10).map(|i| [i,i * i]).collect::<Vec<_>>());
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_bincode_encoding(){
+	let dir = temp_dir("tiered-bincode");
+	let mut t = Tiered::new(2,FileSink::new(&dir,64,BincodeEncoding).unwrap());
+	for i in 0..10u32{
+		t.queue((i,"x".repeat(i as usize))).unwrap();
+	}
+	let all: Vec<(u32,String)> = t.iter_all().unwrap().map(Result::unwrap).collect();
+	assert_eq!(all,(0..10).map(|i| (i,"x".repeat(i as usize))).collect::<Vec<_>>());
+	fs::remove_dir_all(&dir).unwrap();
+}
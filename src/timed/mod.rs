@@ -0,0 +1,249 @@
+//!Buffer of timestamped values, bounded both by a capacity and by the age of its values.
+
+#[cfg(test)]
+mod test;
+
+use core::cell::Cell;
+use std::time::{Duration,Instant};
+
+use CircularBuffer;
+
+///Source of timestamps.
+pub trait Clock{
+	type Instant: Copy + Ord;
+
+	fn now(&self) -> Self::Instant;
+}
+
+///Clock of `std::time::Instant`.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock{
+	type Instant = Instant;
+
+	#[inline(always)]
+	fn now(&self) -> Instant{Instant::now()}
+}
+
+///Clock which only moves when told to, for example in tests.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct ManualClock{
+	now: Cell<u64>,
+}
+
+impl ManualClock{
+	#[inline]
+	pub fn new(now: u64) -> Self{
+		ManualClock{now: Cell::new(now)}
+	}
+
+	#[inline]
+	pub fn set(&self,now: u64){
+		self.now.set(now);
+	}
+
+	#[inline]
+	pub fn advance(&self,duration: u64){
+		self.now.set(self.now.get() + duration);
+	}
+}
+
+impl Clock for ManualClock{
+	type Instant = u64;
+
+	#[inline(always)]
+	fn now(&self) -> u64{self.now.get()}
+}
+
+///Subtraction of a duration from an instant, which is `None` when the result is not representable, such as before the epoch of the clock.
+pub trait CheckedSub<D>: Sized{
+	fn checked_sub(self,duration: D) -> Option<Self>;
+}
+
+impl CheckedSub<Duration> for Instant{
+	#[inline(always)]
+	fn checked_sub(self,duration: Duration) -> Option<Instant>{Instant::checked_sub(&self,duration)}
+}
+
+impl CheckedSub<u64> for u64{
+	#[inline(always)]
+	fn checked_sub(self,duration: u64) -> Option<u64>{u64::checked_sub(self,duration)}
+}
+
+impl<C: Clock> Clock for &C{
+	type Instant = C::Instant;
+
+	#[inline(always)]
+	fn now(&self) -> C::Instant{(**self).now()}
+}
+
+///Buffer of up to `capacity` values paired with their timestamps, which also drops values older than a given time on request.
+///
+///Timestamps never decrease from the oldest to the most recently pushed value, so values can be searched by time.
+///Like in `CircularBuffer`, index 0 is the most recently pushed value.
+#[derive(Clone,Debug)]
+pub struct TimeWindow<T,C: Clock>{
+	buffer: CircularBuffer<Option<(C::Instant,T)>>,
+	len: usize,
+	clock: C,
+	dropped_by_capacity: u64,
+	dropped_by_age: u64,
+}
+
+impl<T,C: Clock> TimeWindow<T,C>{
+	///Constructs an empty window holding up to `capacity` values.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0.
+	pub fn new(capacity: usize,clock: C) -> Self{
+		assert!(capacity > 0);
+		TimeWindow{
+			buffer: CircularBuffer::from((0..capacity).map(|_| None).collect::<Vec<_>>()),
+			len: 0,
+			clock,
+			dropped_by_capacity: 0,
+			dropped_by_age: 0,
+		}
+	}
+
+	#[inline(always)]
+	pub fn capacity(&self) -> usize{self.buffer.len()}
+
+	#[inline(always)]
+	pub fn len(&self) -> usize{self.len}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool{self.len == 0}
+
+	#[inline(always)]
+	pub fn clock(&self) -> &C{&self.clock}
+
+	///Returns the number of values dropped because the capacity was reached.
+	#[inline(always)]
+	pub fn dropped_by_capacity(&self) -> u64{self.dropped_by_capacity}
+
+	///Returns the number of values dropped because they expired.
+	#[inline(always)]
+	pub fn dropped_by_age(&self) -> u64{self.dropped_by_age}
+
+	///Returns the value at `index` and its timestamp, where 0 is the most recently pushed value.
+	#[inline]
+	pub fn get(&self,index: usize) -> Option<(C::Instant,&T)>{
+		if index < self.len{
+			self.buffer.get(index).as_ref().map(|&(t,ref value)| (t,value))
+		}else{
+			None
+		}
+	}
+
+	///Returns the most recently pushed value and its timestamp.
+	#[inline]
+	pub fn newest(&self) -> Option<(C::Instant,&T)>{self.get(0)}
+
+	///Returns the oldest value and its timestamp.
+	#[inline]
+	pub fn oldest(&self) -> Option<(C::Instant,&T)>{
+		self.len.checked_sub(1).and_then(|index| self.get(index))
+	}
+
+	///Returns an iterator over the values and their timestamps, from the most recently pushed to the oldest.
+	#[inline]
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item=(C::Instant,&T)> + ExactSizeIterator{
+		self.iter_first(self.len)
+	}
+
+	///Returns an iterator over the values with timestamps at or after `t`, from the most recently pushed to the oldest.
+	#[inline]
+	pub fn iter_since(&self,t: C::Instant) -> impl DoubleEndedIterator<Item=(C::Instant,&T)> + ExactSizeIterator{
+		self.iter_first(self.count_since(t))
+	}
+
+	///Returns the number of values with timestamps at or after `t`, which are the ones at the indices `0..count`.
+	#[inline]
+	pub fn count_since(&self,t: C::Instant) -> usize{
+		self.partition_point(|timestamp| timestamp >= t)
+	}
+
+	///Binary searches for a value with the timestamp `t` (See `slice::binary_search`).
+	///Returns the index of a matching value,
+	///or the index where a value with that timestamp would be if timestamps were allowed to be inserted in between.
+	pub fn binary_search(&self,t: C::Instant) -> Result<usize,usize>{
+		let index = self.partition_point(|timestamp| timestamp > t);
+		match self.get(index){
+			Some((timestamp,_)) if timestamp == t => Ok(index),
+			_ => Err(index),
+		}
+	}
+
+	///Pushes a value with the current time of the clock, returning the oldest value when the capacity is reached.
+	///
+	///# Panics
+	///
+	///When the clock went backwards since the most recently pushed value.
+	#[inline]
+	pub fn push(&mut self,value: T) -> Option<(C::Instant,T)>{
+		let now = self.clock.now();
+		self.push_at(now,value)
+	}
+
+	///Pushes a value with the given timestamp, returning the oldest value when the capacity is reached.
+	///
+	///# Panics
+	///
+	///When `t` is earlier than the timestamp of the most recently pushed value.
+	pub fn push_at(&mut self,t: C::Instant,value: T) -> Option<(C::Instant,T)>{
+		if let Some((newest,_)) = self.newest(){
+			assert!(t >= newest,"timestamps must not decrease");
+		}
+		let out = self.buffer.queue(Some((t,value)));
+		if out.is_some(){
+			self.dropped_by_capacity += 1;
+		}else{
+			self.len += 1;
+		}
+		out
+	}
+
+	///Drops the values with timestamps before `t`, returning the number of dropped values.
+	pub fn expire_before(&mut self,t: C::Instant) -> usize{
+		let count = self.len - self.count_since(t);
+		for _ in 0..count{
+			self.len -= 1;
+			*self.buffer.get_mut(self.len) = None;
+		}
+		self.dropped_by_age += count as u64;
+		count
+	}
+
+	///Drops the values which are older than `age` according to the clock, returning the number of dropped values.
+	///Nothing is dropped when `age` reaches back before the epoch of the clock.
+	#[inline]
+	pub fn expire_older_than<D>(&mut self,age: D) -> usize where
+		C::Instant: CheckedSub<D>
+	{
+		match self.clock.now().checked_sub(age){
+			Some(t) => self.expire_before(t),
+			None => 0,
+		}
+	}
+
+	///Returns the number of leading indices whose timestamps satisfy `pred`, which must be true up to some index and false after it.
+	fn partition_point<P: Fn(C::Instant) -> bool>(&self,pred: P) -> usize{
+		let (mut low,mut high) = (0,self.len);
+		while low < high{
+			let mid = low + (high - low) / 2;
+			if pred(self.get(mid).unwrap().0){
+				low = mid + 1;
+			}else{
+				high = mid;
+			}
+		}
+		low
+	}
+
+	fn iter_first(&self,count: usize) -> impl DoubleEndedIterator<Item=(C::Instant,&T)> + ExactSizeIterator{
+		(0..count).map(move |index| self.get(index).unwrap())
+	}
+}
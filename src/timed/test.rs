@@ -0,0 +1,91 @@
+use super::*;
+
+use std::time::Duration;
+
+fn values<C: Clock<Instant=u64>>(w: &TimeWindow<char,C>) -> Vec<(u64,char)>{
+	w.iter().map(|(t,&value)| (t,value)).collect()
+}
+
+#[test]
+fn test_capacity_and_age(){
+	let mut w = TimeWindow::new(3,ManualClock::new(10));
+	assert!(w.push('a').is_none());
+	w.clock().advance(5);
+	w.push('b');
+	w.push('c');
+	w.clock().advance(5);
+	assert_eq!(w.push('d'),Some((10,'a')));
+	assert_eq!(values(&w),[(20,'d'),(15,'c'),(15,'b')]);
+	assert_eq!(w.dropped_by_capacity(),1);
+
+	assert_eq!(w.expire_before(15),0);
+	assert_eq!(w.expire_older_than(4u64),2);
+	assert_eq!(values(&w),[(20,'d')]);
+	assert_eq!(w.dropped_by_age(),2);
+	assert_eq!(w.oldest(),w.newest());
+
+	w.clock().advance(100);
+	assert_eq!(w.expire_older_than(10u64),1);
+	assert!(w.is_empty());
+	assert_eq!(w.oldest(),None);
+
+	//Expired slots are reused before anything is dropped by capacity
+	for c in "xyz".chars(){
+		w.push(c);
+	}
+	assert_eq!(w.len(),3);
+	assert_eq!(w.dropped_by_capacity(),1);
+}
+
+#[test]
+fn test_search(){
+	let mut w = TimeWindow::new(8,ManualClock::new(0));
+	for (t,c) in [(1,'a'),(3,'b'),(3,'c'),(6,'d'),(9,'e')]{
+		w.push_at(t,c);
+	}
+	assert_eq!(w.count_since(3),4);
+	assert_eq!(w.count_since(4),2);
+	assert_eq!(w.count_since(0),5);
+	assert_eq!(w.count_since(10),0);
+
+	assert_eq!(w.iter_since(6).map(|(_,&c)| c).collect::<String>(),"ed");
+	assert_eq!(w.iter_since(2).rev().map(|(_,&c)| c).collect::<String>(),"bcde");
+
+	assert_eq!(w.binary_search(9),Ok(0));
+	assert!(matches!(w.binary_search(3),Ok(2) | Ok(3)));
+	assert_eq!(w.binary_search(1),Ok(4));
+	assert_eq!(w.binary_search(5),Err(2));
+	assert_eq!(w.binary_search(10),Err(0));
+	assert_eq!(w.binary_search(0),Err(5));
+}
+
+#[test]
+#[should_panic]
+fn test_decreasing_timestamp(){
+	let mut w = TimeWindow::new(2,ManualClock::new(0));
+	w.push_at(5,'a');
+	w.push_at(4,'b');
+}
+
+#[test]
+fn test_age_before_epoch(){
+	let mut w = TimeWindow::new(2,ManualClock::new(3));
+	w.push('a');
+	assert_eq!(w.expire_older_than(5u64),0);
+	assert_eq!(w.expire_older_than(u64::MAX),0);
+	assert_eq!(w.len(),1);
+
+	let mut w = TimeWindow::new(2,SystemClock);
+	w.push('a');
+	assert_eq!(w.expire_older_than(Duration::MAX),0);
+}
+
+#[test]
+fn test_system_clock(){
+	let mut w = TimeWindow::new(4,SystemClock);
+	w.push(1);
+	w.push(2);
+	assert_eq!(w.expire_older_than(Duration::from_secs(3600)),0);
+	let (newest,_) = w.newest().unwrap();
+	assert!(w.count_since(newest) >= 1);
+}
@@ -0,0 +1,150 @@
+//!Storage mapping the same memory twice, back to back, so that every window of the buffer is contiguous.
+
+#[cfg(test)]
+mod test;
+
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref,DerefMut};
+use core::ptr;
+use core::slice;
+use std::io;
+
+use CircularBuffer;
+
+///Elements in an anonymous memory file (`memfd_create`) which is mapped twice in a row.
+///
+///Dereferences to the `len` elements of the first mapping, while `mirrored` also includes the second mapping, where the same elements repeat.
+#[derive(Debug)]
+pub struct VmemSlice<T>{
+	ptr: *mut T,
+	len: usize,
+	t: PhantomData<Box<[T]>>,
+}
+
+unsafe impl<T: Send> Send for VmemSlice<T>{}
+unsafe impl<T: Sync> Sync for VmemSlice<T>{}
+
+impl<T: Copy> VmemSlice<T>{
+	///Maps at least `capacity` copies of `fill`.
+	///The length is rounded up so that the elements fill whole pages, adding less than a page of elements.
+	///
+	///Fails with `InvalidInput` when the size of `T` does not divide the page size, as the elements would not line up with the pages.
+	///
+	///# Panics
+	///
+	///When `capacity` is 0, or when `T` is zero-sized.
+	pub fn new(capacity: usize,fill: T) -> io::Result<Self>{
+		assert!(capacity > 0 && mem::size_of::<T>() > 0);
+		let page = unsafe{libc::sysconf(libc::_SC_PAGESIZE)} as usize;
+		let size = mem::size_of::<T>();
+		if !page.is_multiple_of(size){
+			return Err(io::Error::new(io::ErrorKind::InvalidInput,"element size does not divide the page size"));
+		}
+
+		let bytes = capacity.checked_mul(size).ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+		let bytes = bytes.div_ceil(page) * page;
+
+		let ptr = unsafe{map_twice(bytes)?} as *mut T;
+		let len = bytes / size;
+		for i in 0..len{
+			unsafe{ptr::write(ptr.add(i),fill);}
+		}
+		Ok(VmemSlice{ptr,len,t: PhantomData})
+	}
+}
+
+impl<T> VmemSlice<T>{
+	///Returns both mappings as one slice of `2 * len` elements, where the element at `i + len` is the element at `i`.
+	#[inline(always)]
+	pub fn mirrored(&self) -> &[T]{
+		unsafe{slice::from_raw_parts(self.ptr,2 * self.len)}
+	}
+}
+
+impl<T> Deref for VmemSlice<T>{
+	type Target = [T];
+
+	#[inline(always)]
+	fn deref(&self) -> &[T]{
+		unsafe{slice::from_raw_parts(self.ptr,self.len)}
+	}
+}
+
+impl<T> DerefMut for VmemSlice<T>{
+	#[inline(always)]
+	fn deref_mut(&mut self) -> &mut [T]{
+		unsafe{slice::from_raw_parts_mut(self.ptr,self.len)}
+	}
+}
+
+impl<T> Drop for VmemSlice<T>{
+	fn drop(&mut self){
+		unsafe{libc::munmap(self.ptr as *mut libc::c_void,2 * self.len * mem::size_of::<T>());}
+	}
+}
+
+///Reserves `2 * bytes` of address space and maps the same memory file of `bytes` bytes to both halves.
+unsafe fn map_twice(bytes: usize) -> io::Result<*mut libc::c_void>{
+	let fd = libc::memfd_create(b"fixed_circular_buffer\0".as_ptr() as *const libc::c_char,libc::MFD_CLOEXEC);
+	if fd < 0{
+		return Err(io::Error::last_os_error());
+	}
+	let result = (|| {
+		if libc::ftruncate(fd,bytes as libc::off_t) != 0{
+			return Err(io::Error::last_os_error());
+		}
+		let base = libc::mmap(ptr::null_mut(),2 * bytes,libc::PROT_NONE,libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,-1,0);
+		if base == libc::MAP_FAILED{
+			return Err(io::Error::last_os_error());
+		}
+		for half in 0..2{
+			let addr = (base as *mut u8).add(half * bytes) as *mut libc::c_void;
+			let mapped = libc::mmap(addr,bytes,libc::PROT_READ | libc::PROT_WRITE,libc::MAP_SHARED | libc::MAP_FIXED,fd,0);
+			if mapped == libc::MAP_FAILED{
+				let error = io::Error::last_os_error();
+				libc::munmap(base,2 * bytes);
+				return Err(error);
+			}
+		}
+		Ok(base)
+	})();
+	libc::close(fd);
+	result
+}
+
+impl<T: Copy> CircularBuffer<T,VmemSlice<T>>{
+	///Constructs a buffer of at least `capacity` copies of `fill` in double mapped memory (See `VmemSlice::new`).
+	#[inline]
+	pub fn new_vmem(capacity: usize,fill: T) -> io::Result<Self>{
+		VmemSlice::new(capacity,fill).map(CircularBuffer::from)
+	}
+}
+
+impl<T> CircularBuffer<T,VmemSlice<T>>{
+	///Returns the `n` elements from the logical index `start` (newest first) as one slice, without copying.
+	///When `start` is out of range, it loops around.
+	///
+	///# Panics
+	///
+	///When `n > self.len()`.
+	#[inline]
+	pub fn window(&self,start: usize,n: usize) -> &[T]{
+		assert!(n <= self.list.len());
+		let start = self.internal_index(start);
+		&self.list.mirrored()[start..start + n]
+	}
+
+	///Returns the `n` elements from the logical index `start` (newest first) as one mutable slice, without copying.
+	///When `start` is out of range, it loops around.
+	///
+	///# Panics
+	///
+	///When `n > self.len()`.
+	#[inline]
+	pub fn window_mut(&mut self,start: usize,n: usize) -> &mut [T]{
+		assert!(n <= self.list.len());
+		let start = self.internal_index(start);
+		unsafe{slice::from_raw_parts_mut(self.list.ptr.add(start),n)}
+	}
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+#[test]
+fn test_rounded_to_pages(){
+	let page = unsafe{libc::sysconf(libc::_SC_PAGESIZE)} as usize;
+	let b = CircularBuffer::new_vmem(10,0u32).unwrap();
+	assert_eq!(b.len(),page / 4);
+	assert!(b.iter().all(|&x| x == 0));
+
+	let b = CircularBuffer::new_vmem(1,[0u8;16]).unwrap();
+	assert_eq!(b.len(),page / 16);
+
+	//Elements which do not line up with the pages
+	assert_eq!(VmemSlice::new(1,[0u8;3]).unwrap_err().kind(),io::ErrorKind::InvalidInput);
+	assert_eq!(VmemSlice::new(1,[0u8;4097]).unwrap_err().kind(),io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_mirrored(){
+	let mut list = VmemSlice::new(1,0u64).unwrap();
+	let len = list.len();
+	list[0] = 1;
+	list[len - 1] = 2;
+	assert_eq!(list.mirrored()[len],1);
+	assert_eq!(list.mirrored()[2 * len - 1],2);
+}
+
+#[test]
+fn test_window(){
+	let mut b = CircularBuffer::new_vmem(1,0u64).unwrap();
+	let len = b.len();
+	for i in 1..=(len as u64 + 3){
+		b.queue(i);
+	}
+	let newest = len as u64 + 3;
+
+	let window = b.window(0,len);
+	assert_eq!(window.len(),len);
+	assert!(window.iter().cloned().eq(b.iter().cloned()));
+	assert_eq!(b.window(2,4),[newest - 2,newest - 3,newest - 4,newest - 5]);
+	assert_eq!(b.window(len + 1,2),[newest - 1,newest - 2]);
+	assert_eq!(b.window(len - 1,2),[newest - len as u64 + 1,newest]);
+
+	b.window_mut(len - 1,2).copy_from_slice(&[7,8]);
+	assert_eq!(*b.get(0),8);
+	assert_eq!(*b.get(len - 1),7);
+}
+
+#[test]
+#[should_panic]
+fn test_window_too_long(){
+	let b = CircularBuffer::new_vmem(1,0u8).unwrap();
+	b.window(0,b.len() + 1);
+}